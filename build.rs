@@ -1,7 +1,23 @@
 use cargo_metadata::MetadataCommand;
 use copy_dir::copy_dir;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
-use std::path::PathBuf;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+#[allow(dead_code)]
+#[path = "src/metadata.rs"]
+mod metadata;
+#[allow(dead_code)]
+#[path = "src/partial.rs"]
+mod partial;
+#[allow(dead_code)]
+#[path = "src/wit.rs"]
+mod wit;
+
+use metadata::{ExampleMetadata, GuestLanguage, TemplateParameter};
+use strum::IntoEnumIterator;
+use wit::{WitBundle, WitPackageName};
 
 fn main() {
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
@@ -11,18 +27,28 @@ fn main() {
 
     println!("cargo:warning=Output dir: {out_dir:?}");
     println!("cargo:warning=Golem WIT root: {golem_wit_root:?}");
+    println!(
+        "cargo:rustc-env=GOLEM_WIT_VERSION={}",
+        package_version(&golem_wit_root)
+    );
 
     let target = out_dir.join("golem-wit");
     if target.exists() {
         if dir_diff::is_different(&golem_wit_root, &target).unwrap_or(true) {
             std::fs::remove_dir_all(&target).unwrap();
-            copy_dir(golem_wit_root, target).unwrap();
+            copy_dir(&golem_wit_root, &target).unwrap();
         } else {
             println!("cargo:warning=Golem WIT is up to date in {target:?}");
         }
     } else {
-        copy_dir(golem_wit_root, target).unwrap();
+        copy_dir(&golem_wit_root, &target).unwrap();
     }
+
+    generate_example_metadata(
+        Path::new("examples"),
+        &wit_bundle(&golem_wit_root.join("wit").join("deps")),
+        &out_dir.join("example_metadata.rs"),
+    );
 }
 
 fn find_package_root(name: &str) -> String {
@@ -33,3 +59,444 @@ fn find_package_root(name: &str) -> String {
     let package = metadata.packages.iter().find(|p| p.name == name).unwrap();
     package.manifest_path.parent().unwrap().to_string()
 }
+
+/// Version from the `[package]` section of the manifest, recorded in the template lock files
+fn package_version(package_root: &Path) -> String {
+    std::fs::read_to_string(package_root.join("Cargo.toml"))
+        .ok()
+        .and_then(|manifest| {
+            manifest
+                .lines()
+                .skip_while(|line| line.trim() != "[package]")
+                .find_map(|line| {
+                    let (key, value) = line.split_once('=')?;
+                    (key.trim() == "version").then(|| value.trim().trim_matches('"').to_string())
+                })
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Collects the WIT packages of the golem-wit deps directory, with the same paths as the
+/// embedded bundle of `lib.rs`
+fn wit_bundle(wit_deps_root: &Path) -> WitBundle {
+    let package_dirs = sorted_dirs(wit_deps_root)
+        .into_iter()
+        .map(|package_dir| {
+            let mut files = std::fs::read_dir(&package_dir)
+                .unwrap_or_else(|err| panic!("Failed to read {}: {}", package_dir.display(), err))
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.is_file())
+                .collect::<Vec<_>>();
+            files.sort();
+            let sources = files
+                .iter()
+                .filter_map(|file| std::fs::read_to_string(file).ok())
+                .collect::<Vec<_>>();
+            (PathBuf::from(file_name(&package_dir)), sources)
+        })
+        .collect::<Vec<_>>();
+    WitBundle::from_sources(package_dirs.iter().map(|(path, sources)| {
+        (
+            path.clone(),
+            sources.iter().map(String::as_str).collect::<Vec<_>>(),
+        )
+    }))
+}
+
+/// Validates the metadata, WIT dependencies and instructions of every example, and generates the
+/// static catalog used by `lib.rs`, so broken examples fail the build instead of panicking at
+/// runtime
+fn generate_example_metadata(examples_root: &Path, wit_bundle: &WitBundle, target: &Path) {
+    let mut errors = vec![];
+    let mut code = String::from("vec![\n");
+    let mut app_commons = BTreeMap::<(String, String), String>::new();
+
+    for lang_dir in sorted_dirs(examples_root) {
+        let lang_dir_name = file_name(&lang_dir);
+        if lang_dir_name == "partials" {
+            continue;
+        }
+        let Some(language) = GuestLanguage::from_string(&lang_dir_name) else {
+            errors.push(format!(
+                "{}: unknown guest language directory, expected one of: {}",
+                lang_dir.display(),
+                GuestLanguage::iter()
+                    .flat_map(|language| language.aliases().iter().copied())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+            continue;
+        };
+        for example_dir in sorted_dirs(&lang_dir) {
+            let example_dir_name = file_name(&example_dir);
+            if example_dir_name == "INSTRUCTIONS" || example_dir_name.starts_with('.') {
+                continue;
+            }
+
+            let metadata_path = example_dir.join("metadata.json");
+            let metadata = match std::fs::read(&metadata_path)
+                .map_err(|err| err.to_string())
+                .and_then(|raw_metadata| ExampleMetadata::from_json(&raw_metadata))
+            {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    errors.push(format!("{}: {}", metadata_path.display(), err));
+                    continue;
+                }
+            };
+
+            let resolved = validate_example(&metadata).and_then(|_| {
+                Ok((
+                    example_instructions(examples_root, &lang_dir, &metadata)?,
+                    declared_wit_deps(wit_bundle, &metadata)?,
+                    inferred_wit_deps(wit_bundle, &example_dir, &metadata)?,
+                ))
+            });
+            let (instructions, declared_wit_deps, inferred_wit_deps) = match resolved {
+                Ok(resolved) => resolved,
+                Err(err) => {
+                    errors.push(format!("{}: {}", metadata_path.display(), err));
+                    continue;
+                }
+            };
+
+            if let Some(group) = &metadata.app_common_group {
+                if let Some(common) = app_commons.insert(
+                    (lang_dir_name.clone(), group.clone()),
+                    example_dir_name.clone(),
+                ) {
+                    errors.push(format!(
+                        "{}: multiple common templates for group {}, the other one is {}",
+                        metadata_path.display(),
+                        group,
+                        common
+                    ));
+                    continue;
+                }
+            }
+
+            writeln!(
+                code,
+                "    crate::metadata::ExampleMetadataEntry {{\n        language_dir: {:?},\n        language: crate::metadata::GuestLanguage::{:?},\n        example_dir: {:?},\n        metadata: {},\n        instructions: {:?},\n        declared_wit_deps: {},\n        inferred_wit_deps: {},\n    }},",
+                lang_dir_name,
+                language,
+                example_dir_name,
+                metadata_code(&metadata),
+                instructions,
+                option_code(&declared_wit_deps, paths_code),
+                option_code(&inferred_wit_deps, paths_code),
+            )
+            .unwrap();
+        }
+    }
+    code.push_str("]\n");
+
+    if !errors.is_empty() {
+        eprintln!("Invalid examples:");
+        for error in &errors {
+            eprintln!("  {error}");
+        }
+        std::process::exit(1);
+    }
+
+    std::fs::write(target, code).unwrap();
+}
+
+fn validate_example(metadata: &ExampleMetadata) -> Result<(), String> {
+    let mut parameter_names = std::collections::HashSet::new();
+    for parameter in metadata.parameters.iter().flatten() {
+        if parameter.name.is_empty() || parameter.placeholder.is_empty() {
+            return Err("Template parameters must have a name and a placeholder".to_string());
+        }
+        if !parameter_names.insert(&parameter.name) {
+            return Err(format!("Duplicated template parameter: {}", parameter.name));
+        }
+    }
+
+    match (&metadata.app_common_group, &metadata.app_component_group) {
+        (Some(_), Some(_)) => {
+            Err("Only one of appCommonGroup and appComponentGroup can be specified".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Instructions of a standalone example with the partials expanded, composable app templates
+/// have no instructions
+fn example_instructions(
+    examples_root: &Path,
+    lang_dir: &Path,
+    metadata: &ExampleMetadata,
+) -> Result<String, String> {
+    if metadata.app_common_group.is_some() || metadata.app_component_group.is_some() {
+        return Ok(String::new());
+    }
+
+    let instructions_path =
+        lang_dir.join(metadata.instructions.as_deref().unwrap_or("INSTRUCTIONS"));
+    let instructions = std::fs::read(&instructions_path).map_err(|err| {
+        format!(
+            "Failed to read instructions {}: {}",
+            instructions_path.display(),
+            err
+        )
+    })?;
+    let instructions = String::from_utf8(instructions).map_err(|err| {
+        format!(
+            "Failed to decode instructions {}: {}",
+            instructions_path.display(),
+            err
+        )
+    })?;
+    partial::expand_partial_directives(&instructions_path, &instructions, &|path| {
+        std::fs::read_to_string(examples_root.join(path)).ok()
+    })
+    .map_err(|err| format!("Failed to expand partials in instructions: {err}"))
+}
+
+/// Resolves the WIT dependencies declared in the metadata, returns None if none of the related
+/// fields are set
+fn declared_wit_deps(
+    wit_bundle: &WitBundle,
+    metadata: &ExampleMetadata,
+) -> Result<Option<BTreeSet<PathBuf>>, String> {
+    if metadata.requires_golem_host_wit.is_none()
+        && metadata.requires_wasi.is_none()
+        && metadata.wit_deps.is_none()
+    {
+        return Ok(None);
+    }
+
+    let mut packages = BTreeSet::<WitPackageName>::new();
+    if metadata.requires_golem_host_wit.unwrap_or(false) {
+        packages.extend(
+            wit::GOLEM_HOST_WIT_PRESET
+                .iter()
+                .map(|name| name.parse().unwrap()),
+        );
+    }
+    if metadata.requires_wasi.unwrap_or(false) {
+        packages.extend(wit::WASI_PRESET.iter().map(|name| name.parse().unwrap()));
+    }
+    for name in metadata.wit_deps.iter().flatten() {
+        packages.insert(name.parse()?);
+    }
+    wit_bundle
+        .resolve_closure(&packages)
+        .map(Some)
+        .map_err(|err| format!("Failed to resolve the declared WIT dependencies: {err}"))
+}
+
+fn metadata_code(metadata: &ExampleMetadata) -> String {
+    // Destructured, so adding a field without generating it fails the build
+    let ExampleMetadata {
+        schema,
+        description,
+        tags,
+        app_common_group,
+        app_common_skip_if_exists,
+        app_component_group,
+        requires_adapter,
+        adapter_target,
+        requires_golem_host_wit,
+        requires_wasi,
+        wit_deps,
+        wit_deps_paths,
+        exclude,
+        instructions,
+        transform_exclude,
+        transform,
+        parameters,
+    } = metadata;
+
+    let fields = [
+        ("schema", option_code(schema, string_code)),
+        ("description", string_code(description)),
+        ("tags", option_code(tags, strings_code)),
+        (
+            "app_common_group",
+            option_code(app_common_group, string_code),
+        ),
+        (
+            "app_common_skip_if_exists",
+            option_code(app_common_skip_if_exists, string_code),
+        ),
+        (
+            "app_component_group",
+            option_code(app_component_group, string_code),
+        ),
+        ("requires_adapter", format!("{requires_adapter:?}")),
+        ("adapter_target", option_code(adapter_target, string_code)),
+        (
+            "requires_golem_host_wit",
+            format!("{requires_golem_host_wit:?}"),
+        ),
+        ("requires_wasi", format!("{requires_wasi:?}")),
+        ("wit_deps", option_code(wit_deps, strings_code)),
+        ("wit_deps_paths", option_code(wit_deps_paths, strings_code)),
+        ("exclude", option_code(exclude, strings_code)),
+        ("instructions", option_code(instructions, string_code)),
+        (
+            "transform_exclude",
+            option_code(transform_exclude, strings_code),
+        ),
+        ("transform", format!("{transform:?}")),
+        (
+            "parameters",
+            option_code(parameters, |parameters| {
+                template_parameters_code(parameters)
+            }),
+        ),
+    ];
+
+    let mut code = String::from("crate::metadata::ExampleMetadata {\n");
+    for (name, value) in fields {
+        writeln!(code, "            {name}: {value},").unwrap();
+    }
+    code.push_str("        }");
+    code
+}
+
+fn string_code(value: &impl AsRef<str>) -> String {
+    format!("{:?}.to_string()", value.as_ref())
+}
+
+fn strings_code(values: &impl AsRef<[String]>) -> String {
+    format!(
+        "vec![{}]",
+        values
+            .as_ref()
+            .iter()
+            .map(string_code)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Resolves the WIT dependencies referenced from the example's WIT files, returns None if the
+/// example has no WIT files
+fn inferred_wit_deps(
+    wit_bundle: &WitBundle,
+    example_dir: &Path,
+    metadata: &ExampleMetadata,
+) -> Result<Option<BTreeSet<PathBuf>>, String> {
+    let mut sources = vec![];
+    collect_wit_sources(
+        example_dir,
+        example_dir,
+        metadata.exclude.as_deref().unwrap_or_default(),
+        &mut sources,
+    )?;
+    if sources.is_empty() {
+        return Ok(None);
+    }
+
+    let mut packages = BTreeSet::<WitPackageName>::new();
+    for source in &sources {
+        let own_package = wit::parse_package_declaration(source);
+        packages.extend(
+            wit::parse_package_references(source).into_iter().filter(
+                |package| match &own_package {
+                    Some(own_package) => !package.matches(own_package),
+                    None => true,
+                },
+            ),
+        );
+    }
+    wit_bundle
+        .resolve_closure(&packages)
+        .map(Some)
+        .map_err(|err| {
+            format!("Failed to resolve the WIT dependencies inferred from the WIT files: {err}")
+        })
+}
+
+/// Collects the contents of the example's `wit/*.wit` files
+fn collect_wit_sources(
+    example_dir: &Path,
+    dir: &Path,
+    exclude: &[String],
+    sources: &mut Vec<String>,
+) -> Result<(), String> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect()
+        })
+        .map_err(|err: std::io::Error| format!("Failed to read {}: {}", dir.display(), err))?;
+    entries.sort();
+    for path in entries {
+        let name = file_name(&path);
+        let relative_path = path.strip_prefix(example_dir).unwrap();
+        if exclude
+            .iter()
+            .any(|entry| metadata::exclude_entry_matches(entry, relative_path))
+        {
+            continue;
+        }
+        if path.is_dir() {
+            collect_wit_sources(example_dir, &path, exclude, sources)?;
+        } else if file_name(dir) == "wit" && name.ends_with(".wit") {
+            sources.push(
+                std::fs::read_to_string(&path)
+                    .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn paths_code(paths: &BTreeSet<PathBuf>) -> String {
+    format!(
+        "vec![{}]",
+        paths
+            .iter()
+            .map(|path| format!("{:?}", path.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn template_parameters_code(parameters: &[TemplateParameter]) -> String {
+    let parameters = parameters
+        .iter()
+        .map(|parameter| {
+            let TemplateParameter {
+                name,
+                description,
+                placeholder,
+                default,
+            } = parameter;
+            format!(
+                "crate::metadata::TemplateParameter {{ name: {}, description: {}, placeholder: {}, default: {} }}",
+                string_code(name),
+                string_code(description),
+                string_code(placeholder),
+                option_code(default, string_code)
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("vec![{}]", parameters.join(", "))
+}
+
+fn option_code<T>(value: &Option<T>, code: impl Fn(&T) -> String) -> String {
+    match value {
+        Some(value) => format!("Some({})", code(value)),
+        None => "None".to_string(),
+    }
+}
+
+fn sorted_dirs(path: &Path) -> Vec<PathBuf> {
+    let mut dirs = std::fs::read_dir(path)
+        .unwrap_or_else(|err| panic!("Failed to read {}: {}", path.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().to_string()
+}
@@ -0,0 +1,263 @@
+use crate::model::{Example, ExampleKind, GuestLanguage, GuestLanguageTier};
+use crate::{COMPONENT_NAME_PLACEHOLDERS, PACKAGE_NAME_PLACEHOLDERS, WIT_PACKAGES};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::Formatter;
+use strum::IntoEnumIterator;
+
+/// Version of the catalog JSON representation, has to be increased on every incompatible change
+pub const CATALOG_SCHEMA_VERSION: u32 = 1;
+
+/// Machine-readable representation of the example catalog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Catalog {
+    pub schema_version: u32,
+    pub languages: Vec<CatalogLanguage>,
+    pub tiers: Vec<CatalogTier>,
+    pub examples: Vec<CatalogExample>,
+    pub groups: Vec<CatalogGroup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogLanguage {
+    pub id: String,
+    pub name: String,
+    pub tier: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogTier {
+    pub name: String,
+    pub level: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CatalogExampleKind {
+    Standalone,
+    ComposableAppCommon,
+    ComposableAppComponent,
+}
+
+impl fmt::Display for CatalogExampleKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogExampleKind::Standalone => write!(f, "standalone"),
+            CatalogExampleKind::ComposableAppCommon => write!(f, "app common"),
+            CatalogExampleKind::ComposableAppComponent => write!(f, "app component"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogExample {
+    pub name: String,
+    /// Language id, see `languages`
+    pub language: String,
+    /// Tier name, see `tiers`
+    pub tier: String,
+    pub kind: CatalogExampleKind,
+    /// Composable app group name, only set for composable app templates
+    pub group: Option<String>,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub parameters: Vec<CatalogParameter>,
+    /// WIT packages copied into the generated project
+    pub wit_deps: Vec<String>,
+    pub adapter: Option<CatalogAdapter>,
+    /// Instructions template, placeholders are replaced the same way as in the template files
+    pub instructions: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogParameter {
+    pub name: String,
+    pub description: String,
+    pub default: Option<String>,
+    /// Placeholders replaced by the parameter in the template file names and contents
+    pub placeholders: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogAdapter {
+    pub tier: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogGroup {
+    /// Language id, see `languages`
+    pub language: String,
+    pub name: String,
+    /// Name of the common template of the group
+    pub common: Option<String>,
+    /// Names of the component templates of the group
+    pub components: Vec<String>,
+}
+
+impl Catalog {
+    /// Creates the catalog representation of the examples, groups are collected from the composable app templates
+    pub fn new<'a>(examples: impl IntoIterator<Item = &'a Example>) -> Catalog {
+        let examples: Vec<&Example> = examples.into_iter().collect();
+
+        let mut groups = BTreeMap::<(GuestLanguage, String), CatalogGroup>::new();
+        for example in &examples {
+            let (group, is_common) = match &example.kind {
+                ExampleKind::Standalone => continue,
+                ExampleKind::ComposableAppCommon { group, .. } => (group, true),
+                ExampleKind::ComposableAppComponent { group } => (group, false),
+            };
+            let catalog_group = groups
+                .entry((example.language, group.to_string()))
+                .or_insert_with(|| CatalogGroup {
+                    language: example.language.id(),
+                    name: group.to_string(),
+                    common: None,
+                    components: vec![],
+                });
+            if is_common {
+                catalog_group.common = Some(example.name.to_string());
+            } else {
+                catalog_group.components.push(example.name.to_string());
+            }
+        }
+
+        Catalog {
+            schema_version: CATALOG_SCHEMA_VERSION,
+            languages: GuestLanguage::iter()
+                .map(|language| CatalogLanguage {
+                    id: language.id(),
+                    name: language.name().to_string(),
+                    tier: language.tier().name().to_string(),
+                })
+                .collect(),
+            tiers: GuestLanguageTier::iter()
+                .map(|tier| CatalogTier {
+                    name: tier.name().to_string(),
+                    level: tier.level(),
+                })
+                .collect(),
+            examples: examples.into_iter().map(CatalogExample::new).collect(),
+            groups: groups.into_values().collect(),
+        }
+    }
+}
+
+impl CatalogExample {
+    fn new(example: &Example) -> CatalogExample {
+        let (kind, group) = match &example.kind {
+            ExampleKind::Standalone => (CatalogExampleKind::Standalone, None),
+            ExampleKind::ComposableAppCommon { group, .. } => {
+                (CatalogExampleKind::ComposableAppCommon, Some(group))
+            }
+            ExampleKind::ComposableAppComponent { group } => {
+                (CatalogExampleKind::ComposableAppComponent, Some(group))
+            }
+        };
+
+        CatalogExample {
+            name: example.name.to_string(),
+            language: example.language.id(),
+            tier: example.language.tier().name().to_string(),
+            kind,
+            group: group.map(|group| group.to_string()),
+            description: example.description.clone(),
+            tags: example.tags.clone(),
+            parameters: builtin_parameters()
+                .into_iter()
+                .chain(example.parameters.iter().map(|parameter| CatalogParameter {
+                    name: parameter.name.clone(),
+                    description: parameter.description.clone(),
+                    default: parameter.default.clone(),
+                    placeholders: vec![parameter.placeholder.clone()],
+                }))
+                .collect(),
+            wit_deps: example
+                .wit_deps
+                .iter()
+                .map(|path| match WIT_PACKAGES.find_by_path(path) {
+                    Some(package) => package.name.to_string(),
+                    None => path.display().to_string(),
+                })
+                .collect(),
+            adapter: example.adapter_source.as_ref().map(|_| CatalogAdapter {
+                tier: example.language.tier().name().to_string(),
+            }),
+            instructions: example.instructions.clone(),
+        }
+    }
+}
+
+fn builtin_parameters() -> Vec<CatalogParameter> {
+    vec![
+        CatalogParameter {
+            name: "componentName".to_string(),
+            description: "Name of the generated component".to_string(),
+            default: None,
+            placeholders: COMPONENT_NAME_PLACEHOLDERS
+                .iter()
+                .map(|placeholder| placeholder.to_string())
+                .collect(),
+        },
+        CatalogParameter {
+            name: "packageName".to_string(),
+            description: "Package name of the generated component, in namespace:name format"
+                .to_string(),
+            default: Some("golem:component".to_string()),
+            placeholders: PACKAGE_NAME_PLACEHOLDERS
+                .iter()
+                .map(|placeholder| placeholder.to_string())
+                .collect(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalog::Catalog;
+    use crate::{all_composable_app_examples, all_standalone_examples};
+
+    #[test]
+    pub fn catalog_schema_is_up_to_date() {
+        let schema = serde_json::to_string_pretty(&schemars::schema_for!(Catalog)).unwrap();
+        assert_eq!(
+            schema.trim(),
+            include_str!("../schemas/catalog.schema.json").trim(),
+            "The catalog JSON schema is outdated, update it with: cargo run -- schema catalog > schemas/catalog.schema.json"
+        );
+    }
+
+    #[test]
+    pub fn catalog_groups() {
+        let app_examples = all_composable_app_examples();
+        let examples = app_examples
+            .values()
+            .flat_map(|groups| groups.values())
+            .flat_map(|app_example| app_example.common.iter().chain(&app_example.components))
+            .collect::<Vec<_>>();
+        let catalog = Catalog::new(examples);
+
+        let rust_default = catalog
+            .groups
+            .iter()
+            .find(|group| group.language == "rust" && group.name == "default")
+            .unwrap();
+        assert_eq!(rust_default.common.as_deref(), Some("rust-app-common"));
+        assert_eq!(rust_default.components, vec!["rust-app-component"]);
+    }
+
+    #[test]
+    pub fn catalog_roundtrip() {
+        let catalog = Catalog::new(&all_standalone_examples());
+        let json = serde_json::to_string(&catalog).unwrap();
+        assert_eq!(serde_json::from_str::<Catalog>(&json).unwrap(), catalog);
+    }
+}
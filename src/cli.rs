@@ -1,19 +1,29 @@
 use clap::*;
+use clap_complete::engine::{ArgValueCandidates, CompletionCandidate};
+use clap_complete::env::EnvCompleter;
+use strum::IntoEnumIterator;
 
+use crate::all_examples;
+use crate::catalog::{Catalog, CatalogExampleKind};
 use crate::model::{
     ComponentName, ComposableAppGroupName, ExampleName, GuestLanguage, GuestLanguageTier,
-    PackageName,
+    PackageName, TargetExistsResolveMode,
 };
+use glob::Pattern;
+use itertools::Itertools;
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::path::PathBuf;
 
 #[derive(Args, Debug)]
-#[group(required = true, multiple = false)]
+#[group(required = false, multiple = false)]
 pub struct NameOrLanguage {
     /// Name of the example to use
-    #[arg(short, long, group = "ex")]
+    #[arg(short, long, group = "ex", add = ArgValueCandidates::new(standalone_example_candidates))]
     pub example: Option<ExampleName>,
 
     /// Language to use for it's default example
-    #[arg(short, long, alias = "lang", group = "ex")]
+    #[arg(short, long, alias = "lang", group = "ex", add = ArgValueCandidates::new(language_candidates))]
     pub language: Option<GuestLanguage>,
 }
 
@@ -29,10 +39,74 @@ impl NameOrLanguage {
     }
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human readable table
+    #[default]
+    Table,
+    /// Tab separated values without colours and headers, for scripting
+    Plain,
+    /// JSON, the catalog JSON is described by the `schema catalog` command
+    Json,
+    /// YAML, with the same structure as the JSON output
+    Yaml,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnConflict {
+    /// Keep the existing files
+    Skip,
+    /// Merge the files which support it (eg. .gitignore), keep the other existing files
+    Merge,
+    /// Fail before writing anything if any generated file already exists
+    #[default]
+    Fail,
+    /// Rename the existing files to <file name>.bak, then write the new files
+    Backup,
+}
+
+impl OnConflict {
+    pub fn resolve_mode(&self) -> TargetExistsResolveMode {
+        match self {
+            OnConflict::Skip => TargetExistsResolveMode::Skip,
+            OnConflict::Merge => TargetExistsResolveMode::MergeOrSkip,
+            OnConflict::Fail => TargetExistsResolveMode::Fail,
+            OnConflict::Backup => TargetExistsResolveMode::Backup,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// JSON schema of the catalog produced by `--format json`
+    Catalog,
+    /// JSON schema of the example `metadata.json` files
+    Metadata,
+    /// JSON schema of the `.golem-template.json` lock files of generated projects
+    Lock,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl CompletionShell {
+    pub fn completer(&self) -> &'static dyn EnvCompleter {
+        match self {
+            CompletionShell::Bash => &clap_complete::env::Bash,
+            CompletionShell::Zsh => &clap_complete::env::Zsh,
+            CompletionShell::Fish => &clap_complete::env::Fish,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 #[command()]
 pub enum Command {
-    /// Create a new Golem component from built-in examples
+    /// Create a new Golem component from built-in examples, interactively when started without arguments on a terminal
     #[command()]
     New {
         #[command(flatten)]
@@ -42,8 +116,26 @@ pub enum Command {
         #[arg(short, long)]
         package_name: Option<PackageName>,
 
-        /// The new component's name
-        component_name: ComponentName,
+        /// Directory of the generated component, defaults to a new directory named after the component
+        #[arg(long, conflicts_with = "in_place")]
+        target: Option<PathBuf>,
+
+        /// Generate the component into the current directory
+        #[arg(long)]
+        in_place: bool,
+
+        /// How to handle files which already exist in the target directory
+        #[arg(long, value_enum, default_value_t = OnConflict::default())]
+        on_conflict: OnConflict,
+
+        /// Value of a parameter declared by the example, can be repeated
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_template_parameter)]
+        template_parameters: Vec<(String, String)>,
+
+        /// The new component's name, required unless running interactively, requires the example
+        /// or the language
+        #[arg(requires = "ex")]
+        component_name: Option<ComponentName>,
     },
 
     /// Lists the built-in examples available for creating new components
@@ -54,35 +146,487 @@ pub enum Command {
         min_tier: Option<GuestLanguageTier>,
 
         /// Filter examples by a given guest language
-        #[arg(short, long, alias = "lang")]
+        #[arg(short, long, alias = "lang", add = ArgValueCandidates::new(language_candidates))]
         language: Option<GuestLanguage>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::default())]
+        format: OutputFormat,
     },
 
     /// Lists the built-in composable app templates available for creating new components
     #[command()]
     ListAppExamples {
         /// Filter examples by a given guest language
-        #[arg(short, long, alias = "lang")]
+        #[arg(short, long, alias = "lang", add = ArgValueCandidates::new(language_candidates))]
         language: Option<GuestLanguage>,
 
         /// Filter examples by a given composable group name
-        #[arg(short, long, alias = "group")]
+        #[arg(short, long, alias = "group", add = ArgValueCandidates::new(group_candidates))]
         group: Option<ComposableAppGroupName>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::default())]
+        format: OutputFormat,
+    },
+
+    /// Shows what an example generates: files, WIT, dependencies, adapter, parameters and instructions
+    #[command()]
+    Describe {
+        /// The example to describe
+        #[arg(add = ArgValueCandidates::new(example_candidates))]
+        example: ExampleName,
+
+        /// Component name used for rendering the file names and contents
+        #[arg(long, default_value = "my-component")]
+        component_name: ComponentName,
+
+        /// Package name used for rendering the file names and contents (in namespace:name format)
+        #[arg(short, long, default_value = "golem:component")]
+        package_name: PackageName,
+
+        /// Value of a parameter declared by the example, can be repeated
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_template_parameter)]
+        template_parameters: Vec<(String, String)>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::default())]
+        format: OutputFormat,
+    },
+
+    /// Prints a single file of an example as it would be generated, or its generated name
+    #[command()]
+    Render {
+        /// The example containing the file
+        #[arg(add = ArgValueCandidates::new(example_candidates))]
+        example: ExampleName,
+
+        /// Path of the file in the example directory, or in the generated project
+        path: PathBuf,
+
+        /// Component name used for rendering the file name and contents
+        #[arg(long, default_value = "my-component")]
+        component_name: ComponentName,
+
+        /// Package name used for rendering the file name and contents (in namespace:name format)
+        #[arg(short, long, default_value = "golem:component")]
+        package_name: PackageName,
+
+        /// Value of a parameter declared by the example, can be repeated
+        #[arg(long = "param", alias = "params", value_name = "NAME=VALUE", value_parser = parse_template_parameter)]
+        template_parameters: Vec<(String, String)>,
+
+        /// Print the path of the generated file instead of its contents
+        #[arg(long)]
+        name: bool,
+    },
+
+    /// Checks whether the tools required for building the components are installed
+    #[command()]
+    Doctor {
+        /// Check the tools of a guest language, defaults to all languages
+        #[arg(short, long, alias = "lang", conflicts_with = "example", add = ArgValueCandidates::new(language_candidates))]
+        language: Option<GuestLanguage>,
+
+        /// Check the tools required by an example
+        #[arg(short, long, add = ArgValueCandidates::new(example_candidates))]
+        example: Option<ExampleName>,
+    },
+
+    /// Checks the built-in examples for common template authoring mistakes
+    #[command()]
+    Lint {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::default())]
+        format: OutputFormat,
+    },
+
+    /// Prints the JSON schema of the machine-readable outputs
+    #[command()]
+    Schema {
+        /// The schema to print
+        #[arg(value_enum)]
+        kind: SchemaKind,
+    },
+
+    /// Prints the shell completion script, example names, languages and groups are completed from the catalog and the external catalogs listed in GOLEM_EXAMPLES_CATALOGS
+    #[command()]
+    Completions {
+        /// The shell to generate the completions for
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+
+    /// Lists the WASI adapters embedded for the language tiers
+    #[command()]
+    ListAdapters,
+
+    /// Checks the WASI adapters of a generated project against the embedded ones
+    #[command()]
+    CheckAdapters {
+        /// Path of the generated project, defaults to the current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Replace the outdated adapters with the embedded ones
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Synchronizes the vendored WIT packages and adapters of a generated project with the embedded golem-wit bundle
+    #[command()]
+    SyncWit {
+        /// Path of the generated project, defaults to the current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// The example the project was generated from, instead of the templates recorded in the template lock file
+        #[arg(short, long, add = ArgValueCandidates::new(example_candidates))]
+        example: Option<ExampleName>,
+
+        /// Only show the results, without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Renames a component and/or its package in a generated project, after showing a preview of the changes
+    #[command(group(ArgGroup::new("new_names").required(true).multiple(true).args(["component_name", "package_name"])))]
+    Rename {
+        /// Path of the generated project, defaults to the current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Current name of the renamed component, required if the project has multiple components
+        #[arg(long)]
+        component: Option<String>,
+
+        /// New component name
+        #[arg(long)]
+        component_name: Option<ComponentName>,
+
+        /// New package name (in namespace:name format)
+        #[arg(short, long)]
+        package_name: Option<PackageName>,
+
+        /// Only show the preview, without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Apply the changes without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Upgrades a generated project to the current templates, merging the template changes into the files
+    #[command()]
+    Upgrade {
+        /// Path of the generated project, defaults to the current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Only show the changes, without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Shows how a generated project differs from the current output of its templates
+    #[command()]
+    Diff {
+        /// Path of the generated project, defaults to the current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// The example to compare with, instead of the templates recorded in the template lock file
+        #[arg(short, long, add = ArgValueCandidates::new(example_candidates))]
+        example: Option<ExampleName>,
+
+        /// Component name used for rendering the example
+        #[arg(long, requires = "example", default_value = "my-component")]
+        component_name: ComponentName,
+
+        /// Package name used for rendering the example (in namespace:name format)
+        #[arg(short, long, requires = "example", default_value = "golem:component")]
+        package_name: PackageName,
+
+        /// Value of a parameter declared by the example, can be repeated
+        #[arg(long = "param", alias = "params", requires = "example", value_name = "NAME=VALUE", value_parser = parse_template_parameter)]
+        template_parameters: Vec<(String, String)>,
+
+        /// Only show the number of changed lines per file
+        #[arg(long)]
+        stat: bool,
+    },
+
+    /// Regenerates missing files of a generated project from its template
+    #[command()]
+    Restore {
+        /// Glob patterns of the files to restore, relative to the project root, defaults to all files
+        #[arg(value_name = "GLOB")]
+        patterns: Vec<Pattern>,
+
+        /// Path of the generated project, defaults to the current directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// The example to restore from, instead of the templates recorded in the template lock file
+        #[arg(short, long, add = ArgValueCandidates::new(example_candidates))]
+        example: Option<ExampleName>,
+
+        /// Component name used for rendering the example
+        #[arg(long, requires = "example", default_value = "my-component")]
+        component_name: ComponentName,
+
+        /// Package name used for rendering the example (in namespace:name format)
+        #[arg(short, long, requires = "example", default_value = "golem:component")]
+        package_name: PackageName,
+
+        /// Value of a parameter declared by the example, can be repeated
+        #[arg(long = "param", alias = "params", requires = "example", value_name = "NAME=VALUE", value_parser = parse_template_parameter)]
+        template_parameters: Vec<(String, String)>,
+
+        /// Also replace existing files which differ from the template output
+        #[arg(long)]
+        overwrite: bool,
+
+        /// Only show the changes, without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Creates a composable application with multiple components, described by a spec file
+    #[command()]
+    NewApp {
+        /// YAML (or JSON) file listing the components, with their name, language, and optionally their group, template and parameters
+        #[arg(long)]
+        spec: PathBuf,
+
+        /// Directory of the application, must not exist or must be empty, defaults to the current directory
+        #[arg(long)]
+        target: Option<PathBuf>,
     },
 
     NewAppComponent {
-        /// The component name (and package name) of the generated component (in namespace:name format)
-        component_name: PackageName,
+        /// The package name of the generated component (in namespace:name format), also used as the component name unless --component-name is set
+        #[arg(value_name = "PACKAGE_NAME")]
+        package_name: PackageName,
+
+        /// The component name of the generated component, defaults to the package name
+        #[arg(long)]
+        component_name: Option<ComponentName>,
 
         /// Component language
-        #[arg(short, long, alias = "lang")]
+        #[arg(short, long, alias = "lang", add = ArgValueCandidates::new(language_candidates))]
         language: GuestLanguage,
+
+        /// Composable app group of the templates, defaults to the group of the selected template, or to the default group
+        #[arg(short, long, add = ArgValueCandidates::new(group_candidates))]
+        group: Option<ComposableAppGroupName>,
+
+        /// Component template of the group, can be omitted if the group has only one component template
+        #[arg(short, long, add = ArgValueCandidates::new(app_component_template_candidates))]
+        template: Option<ExampleName>,
+
+        /// Value of a parameter declared by the component template, can be repeated
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_template_parameter)]
+        template_parameters: Vec<(String, String)>,
     },
 }
 
+/// Environment variable listing catalog JSON files (see the `schema catalog` command) of
+/// templates provided on top of the embedded catalog, eg. by tools wrapping this CLI, whose
+/// examples and groups are completed too
+pub const EXTERNAL_CATALOGS_VAR: &str = "GOLEM_EXAMPLES_CATALOGS";
+
+/// The embedded catalog and the external catalogs listed in `EXTERNAL_CATALOGS_VAR`
+fn completion_catalogs() -> Vec<Catalog> {
+    let mut catalogs = vec![Catalog::new(&all_examples())];
+    if let Some(paths) = std::env::var_os(EXTERNAL_CATALOGS_VAR) {
+        catalogs.extend(read_catalogs(&paths));
+    }
+    catalogs
+}
+
+/// Reads the catalogs of a path list, unreadable catalogs are skipped, as completions cannot
+/// report errors
+fn read_catalogs(paths: &OsStr) -> Vec<Catalog> {
+    std::env::split_paths(paths)
+        .filter_map(|path| std::fs::read(path).ok())
+        .filter_map(|json| serde_json::from_slice::<Catalog>(&json).ok())
+        .collect()
+}
+
+fn catalog_example_candidates(
+    catalogs: &[Catalog],
+    kinds: &[CatalogExampleKind],
+) -> Vec<CompletionCandidate> {
+    catalogs
+        .iter()
+        .flat_map(|catalog| &catalog.examples)
+        .filter(|example| kinds.is_empty() || kinds.contains(&example.kind))
+        .unique_by(|example| &example.name)
+        .map(|example| {
+            CompletionCandidate::new(&example.name).help(Some(example.description.clone().into()))
+        })
+        .collect()
+}
+
+fn catalog_group_candidates(catalogs: &[Catalog]) -> Vec<CompletionCandidate> {
+    catalogs
+        .iter()
+        .flat_map(|catalog| &catalog.groups)
+        .map(|group| &group.name)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+fn example_candidates() -> Vec<CompletionCandidate> {
+    catalog_example_candidates(&completion_catalogs(), &[])
+}
+
+fn standalone_example_candidates() -> Vec<CompletionCandidate> {
+    catalog_example_candidates(&completion_catalogs(), &[CatalogExampleKind::Standalone])
+}
+
+fn app_component_template_candidates() -> Vec<CompletionCandidate> {
+    catalog_example_candidates(
+        &completion_catalogs(),
+        &[CatalogExampleKind::ComposableAppComponent],
+    )
+}
+
+fn language_candidates() -> Vec<CompletionCandidate> {
+    GuestLanguage::iter()
+        .flat_map(|language| {
+            language.aliases().iter().map(move |alias| {
+                CompletionCandidate::new(alias).help(Some(language.name().into()))
+            })
+        })
+        .collect()
+}
+
+fn group_candidates() -> Vec<CompletionCandidate> {
+    catalog_group_candidates(&completion_catalogs())
+}
+
+fn parse_template_parameter(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("Expected NAME=VALUE, got {s}")),
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None, rename_all = "kebab-case")]
 pub struct GolemCommand {
     #[command(subcommand)]
     pub command: Command,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::all_examples;
+    use crate::catalog::{Catalog, CatalogExampleKind};
+    use crate::cli::{
+        catalog_example_candidates, catalog_group_candidates, read_catalogs, GolemCommand,
+    };
+    use clap::{CommandFactory, Parser};
+    use clap_complete::engine::{complete, CompletionCandidate};
+    use std::ffi::OsString;
+
+    fn completions(args: &[&str]) -> Vec<String> {
+        let args = args.iter().map(OsString::from).collect::<Vec<_>>();
+        complete(
+            &mut GolemCommand::command(),
+            args.clone(),
+            args.len() - 1,
+            None,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|candidate| candidate.get_value().to_string_lossy().to_string())
+        .collect()
+    }
+
+    #[test]
+    pub fn complete_from_catalog() {
+        assert_eq!(
+            completions(&["golem-examples-cli", "new", "--example", "rust-default-m"]),
+            vec!["rust-default-minimal"]
+        );
+        assert_eq!(
+            completions(&["golem-examples-cli", "doctor", "--language", "ty"]),
+            vec!["typescript"]
+        );
+        assert_eq!(
+            completions(&["golem-examples-cli", "new-app-component", "--group", ""]),
+            vec!["default"]
+        );
+        assert!(completions(&[
+            "golem-examples-cli",
+            "new-app-component",
+            "--template",
+            "rust-"
+        ])
+        .contains(&"rust-app-component".to_string()));
+    }
+
+    fn values(candidates: Vec<CompletionCandidate>) -> Vec<String> {
+        candidates
+            .into_iter()
+            .map(|candidate| candidate.get_value().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    pub fn complete_from_external_catalogs() {
+        let embedded = Catalog::new(&all_examples());
+        let mut external = embedded.clone();
+        external.examples.truncate(1);
+        external.examples[0].name = "acme-worker".to_string();
+        external.examples[0].kind = CatalogExampleKind::Standalone;
+        external.groups.truncate(1);
+        external.groups[0].name = "acme".to_string();
+
+        let dir = tempfile::tempdir().unwrap();
+        let external_path = dir.path().join("acme.json");
+        std::fs::write(&external_path, serde_json::to_string(&external).unwrap()).unwrap();
+        let invalid_path = dir.path().join("invalid.json");
+        std::fs::write(&invalid_path, "{").unwrap();
+        let paths =
+            std::env::join_paths([external_path, invalid_path, dir.path().join("missing.json")])
+                .unwrap();
+
+        let external_catalogs = read_catalogs(&paths);
+        assert_eq!(external_catalogs, vec![external]);
+
+        let catalogs = [vec![embedded], external_catalogs].concat();
+        let standalone = values(catalog_example_candidates(
+            &catalogs,
+            &[CatalogExampleKind::Standalone],
+        ));
+        assert!(standalone.contains(&"acme-worker".to_string()));
+        assert!(standalone.contains(&"rust-default".to_string()));
+        assert!(!standalone.contains(&"rust-app-component".to_string()));
+        assert_eq!(
+            values(catalog_group_candidates(&catalogs)),
+            vec!["acme", "default"]
+        );
+    }
+
+    #[test]
+    pub fn new_requires_example_or_language_with_component_name() {
+        let parse = |args: &[&str]| {
+            GolemCommand::try_parse_from([&["golem-examples-cli", "new"], args].concat())
+        };
+        assert_eq!(
+            parse(&["my-component"]).unwrap_err().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        );
+        assert!(parse(&["-l", "go", "my-component"]).is_ok());
+        assert!(parse(&["-e", "rust-default", "my-component"]).is_ok());
+        // Without arguments the interactive mode selects the example
+        assert!(parse(&[]).is_ok());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::error::Result;
+use crate::model::{Example, ExampleName, ExampleParameters, GuestLanguage, TemplateParameter};
+use crate::wit::{parse_interfaces, parse_worlds, WitWorld};
+use crate::wizard::render_file_tree;
+use crate::{
+    adapter_target, planned_files, render_example_instructions, render_template_file,
+    template_files, wit_deps_targets, WIT_PACKAGES,
+};
+use serde::Serialize;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+
+/// What an example generates for the given parameters, see `describe_example`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExampleDescription {
+    pub name: ExampleName,
+    pub language: GuestLanguage,
+    pub description: String,
+    /// Generated files, relative to the target path
+    pub files: Vec<PathBuf>,
+    /// Worlds declared by the WIT files of the template
+    pub worlds: Vec<WitWorld>,
+    /// Interfaces declared by the WIT files of the template
+    pub interfaces: Vec<String>,
+    /// WIT packages copied into the project
+    pub wit_deps: Vec<String>,
+    /// Directories the WIT packages are copied to, relative to the target path
+    pub wit_deps_targets: Vec<PathBuf>,
+    pub adapter: Option<DescribedAdapter>,
+    pub parameters: Vec<TemplateParameter>,
+    pub instructions: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DescribedAdapter {
+    /// Path of the embedded adapter
+    pub source: PathBuf,
+    /// Path of the adapter in the project, relative to the target path
+    pub target: PathBuf,
+}
+
+/// Describes the files, WIT, dependencies, parameters and instructions the example generates,
+/// without writing anything
+pub fn describe_example(
+    example: &Example,
+    parameters: &ExampleParameters,
+) -> Result<ExampleDescription> {
+    let mut worlds = vec![];
+    let mut interfaces = vec![];
+    for file in template_files(example, parameters)?
+        .iter()
+        .filter(|file| file.target.extension().is_some_and(|ext| ext == "wit"))
+    {
+        let contents = render_template_file(example, file, parameters)?;
+        let contents = String::from_utf8_lossy(&contents);
+        worlds.extend(parse_worlds(&contents));
+        interfaces.extend(parse_interfaces(&contents));
+    }
+
+    Ok(ExampleDescription {
+        name: example.name.clone(),
+        language: example.language,
+        description: example.description.clone(),
+        files: planned_files(example, parameters)?,
+        worlds,
+        interfaces,
+        wit_deps: example
+            .wit_deps
+            .iter()
+            .map(|path| match WIT_PACKAGES.find_by_path(path) {
+                Some(package) => package.name.to_string(),
+                None => path.display().to_string(),
+            })
+            .collect(),
+        wit_deps_targets: if example.wit_deps.is_empty() {
+            vec![]
+        } else {
+            wit_deps_targets(example)
+        },
+        adapter: example
+            .adapter_source
+            .clone()
+            .zip(adapter_target(example))
+            .map(|(source, target)| DescribedAdapter { source, target }),
+        parameters: example.parameters.clone(),
+        instructions: render_example_instructions(example, parameters),
+    })
+}
+
+impl fmt::Display for ExampleDescription {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({}): {}", self.name, self.language, self.description)?;
+
+        writeln!(f, "\nFiles:")?;
+        writeln!(f, "{}", render_file_tree(".", &self.files))?;
+
+        if !self.worlds.is_empty() || !self.interfaces.is_empty() {
+            writeln!(f, "\nWIT:")?;
+            for interface in &self.interfaces {
+                writeln!(f, "  interface {interface}")?;
+            }
+            for world in &self.worlds {
+                writeln!(f, "  world {}", world.name)?;
+                for include in &world.includes {
+                    writeln!(f, "    include {include}")?;
+                }
+                for import in &world.imports {
+                    writeln!(f, "    import {import}")?;
+                }
+                for export in &world.exports {
+                    writeln!(f, "    export {export}")?;
+                }
+            }
+        }
+
+        if !self.wit_deps.is_empty() {
+            writeln!(
+                f,
+                "\nWIT dependencies, copied to {}:",
+                self.wit_deps_targets
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+            for wit_dep in &self.wit_deps {
+                writeln!(f, "  {wit_dep}")?;
+            }
+        }
+
+        if let Some(adapter) = &self.adapter {
+            writeln!(
+                f,
+                "\nAdapter: {} (from {})",
+                adapter.target.display(),
+                adapter.source.display()
+            )?;
+        }
+
+        if !self.parameters.is_empty() {
+            writeln!(f, "\nParameters:")?;
+            for parameter in &self.parameters {
+                match &parameter.default {
+                    Some(default) => writeln!(
+                        f,
+                        "  {}: {} (default: {})",
+                        parameter.name, parameter.description, default
+                    )?,
+                    None => writeln!(
+                        f,
+                        "  {}: {} (required)",
+                        parameter.name, parameter.description
+                    )?,
+                }
+            }
+        }
+
+        if !self.instructions.trim().is_empty() {
+            writeln!(f, "\nInstructions:")?;
+            write!(f, "{}", self.instructions.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::describe::describe_example;
+    use crate::test_fixtures::{example, parameters};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    pub fn describe_go_default() {
+        let example = example("go-default");
+        let parameters = parameters(Path::new("my-component"));
+
+        let description = describe_example(&example, &parameters).unwrap();
+        assert!(description
+            .files
+            .contains(&PathBuf::from("wit/my-component.wit")));
+        assert_eq!(description.interfaces, vec!["api"]);
+        assert_eq!(description.worlds.len(), 1);
+        assert_eq!(description.worlds[0].name, "my-component");
+        assert_eq!(description.worlds[0].exports, vec!["api"]);
+        assert_eq!(
+            description.wit_deps_targets,
+            vec![PathBuf::from("wit/deps")]
+        );
+        assert!(description
+            .wit_deps
+            .iter()
+            .any(|package| package.starts_with("golem:api")));
+        assert!(description.adapter.is_some());
+
+        let text = description.to_string();
+        assert!(text.contains("world my-component"));
+        assert!(text.contains("Instructions:"));
+    }
+}
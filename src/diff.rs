@@ -0,0 +1,220 @@
+use crate::check_template_parameters;
+use crate::error::{Error, IoResultExt, Result};
+use crate::lock::{generated_contents, read_template_lock, TEMPLATE_LOCK_FILE_NAME};
+use crate::model::{Example, ExampleParameters};
+use diffy::{DiffOptions, Line, PatchFormatter};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How a project file differs from the template output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileDiffKind {
+    /// The project file differs from the template output
+    Modified,
+    /// The template generates the file, but it is missing from the project
+    Removed,
+    /// The file was generated by the template, which does not generate it anymore
+    Added,
+}
+
+impl fmt::Display for FileDiffKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FileDiffKind::Modified => write!(f, "modified"),
+            FileDiffKind::Removed => write!(f, "removed"),
+            FileDiffKind::Added => write!(f, "added"),
+        }
+    }
+}
+
+/// Difference of a project file from the template output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    pub kind: FileDiffKind,
+    /// Template output, `None` if the template does not generate the file
+    pub template: Option<Vec<u8>>,
+    /// Contents of the project file, `None` if the file is missing
+    pub project: Option<Vec<u8>>,
+}
+
+impl FileDiff {
+    /// Unified diff from the template output to the project file, optionally with ANSI colours
+    pub fn unified_diff(&self, color: bool) -> String {
+        let path = self.path.display();
+        let (Some(template), Some(project)) = (self.text(&self.template), self.text(&self.project))
+        else {
+            return format!("Binary files a/{path} and b/{path} differ\n");
+        };
+        let patch = DiffOptions::new()
+            .set_original_filename(match self.template {
+                Some(_) => format!("a/{path}"),
+                None => "/dev/null".to_string(),
+            })
+            .set_modified_filename(match self.project {
+                Some(_) => format!("b/{path}"),
+                None => "/dev/null".to_string(),
+            })
+            .create_patch(template, project);
+        let formatter = if color {
+            PatchFormatter::new().with_color()
+        } else {
+            PatchFormatter::new()
+        };
+        let unified = formatter.fmt_patch(&patch).to_string();
+        unified
+    }
+
+    /// Number of inserted and deleted lines, `None` for binary files
+    pub fn line_stat(&self) -> Option<(usize, usize)> {
+        let template = self.text(&self.template)?;
+        let project = self.text(&self.project)?;
+        let patch = diffy::create_patch(template, project);
+        let mut insertions = 0;
+        let mut deletions = 0;
+        for line in patch.hunks().iter().flat_map(|hunk| hunk.lines()) {
+            match line {
+                Line::Insert(_) => insertions += 1,
+                Line::Delete(_) => deletions += 1,
+                Line::Context(_) => {}
+            }
+        }
+        Some((insertions, deletions))
+    }
+
+    /// Contents as text, missing contents are empty
+    fn text<'a>(&self, contents: &'a Option<Vec<u8>>) -> Option<&'a str> {
+        match contents {
+            Some(contents) => std::str::from_utf8(contents).ok(),
+            None => Some(""),
+        }
+    }
+}
+
+/// Compares a generated project with the current output of the templates recorded in its lock
+/// file, the templates are rendered in memory
+pub fn diff_project(project_path: &Path) -> Result<Vec<FileDiff>> {
+    let lock = read_template_lock(project_path)?.ok_or_else(|| {
+        Error::validation(format!(
+            "No {TEMPLATE_LOCK_FILE_NAME} file, the example and its parameters have to be provided"
+        ))
+        .with_path(project_path)
+    })?;
+
+    let mut generated = BTreeMap::new();
+    let mut owned = BTreeSet::new();
+    for entry in &lock.templates {
+        let example = entry.find_example()?;
+        let parameters = entry.example_parameters(project_path)?;
+        generated.extend(generated_contents(&example, &parameters)?);
+        owned.extend(entry.files.keys().cloned());
+    }
+    diff_contents(project_path, &generated, &owned)
+}
+
+/// Compares the project at the target path of the parameters with the output of the example
+pub fn diff_example(example: &Example, parameters: &ExampleParameters) -> Result<Vec<FileDiff>> {
+    check_template_parameters(example, parameters)?;
+    diff_contents(
+        &parameters.target_path,
+        &generated_contents(example, parameters)?,
+        &BTreeSet::new(),
+    )
+}
+
+/// Compares the generated contents with the project files, `owned` are the files generated
+/// earlier, which are reported if the template does not generate them anymore
+fn diff_contents(
+    project_path: &Path,
+    generated: &BTreeMap<PathBuf, Vec<u8>>,
+    owned: &BTreeSet<PathBuf>,
+) -> Result<Vec<FileDiff>> {
+    let mut result = vec![];
+    for path in generated.keys().chain(owned).collect::<BTreeSet<_>>() {
+        let target = project_path.join(path);
+        let project = if target.is_file() {
+            Some(fs::read(&target).with_path(&target)?)
+        } else {
+            None
+        };
+        let template = generated.get(path);
+        let kind = match (template, &project) {
+            (Some(template), Some(project)) if template != project => FileDiffKind::Modified,
+            (Some(_), None) => FileDiffKind::Removed,
+            (None, Some(_)) => FileDiffKind::Added,
+            _ => continue,
+        };
+        result.push(FileDiff {
+            path: path.clone(),
+            kind,
+            template: template.cloned(),
+            project,
+        });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::{diff_example, diff_project, FileDiffKind};
+    use crate::lock::read_template_lock;
+    use crate::lock::update_template_lock;
+    use crate::sha256;
+    use crate::test_fixtures::TestProject;
+    use std::path::PathBuf;
+
+    #[test]
+    pub fn diff_generated_project() {
+        let project = TestProject::generated("rust-default");
+        let target_path = project.path();
+        assert!(diff_project(target_path).unwrap().is_empty());
+
+        let wit = target_path.join("wit/my-component.wit");
+        let contents = std::fs::read_to_string(&wit).unwrap();
+        std::fs::write(
+            &wit,
+            contents.replace("export api;", "export api;\n  export extra;"),
+        )
+        .unwrap();
+        std::fs::remove_file(target_path.join("Cargo.toml")).unwrap();
+        std::fs::write(target_path.join("old.txt"), "old\n").unwrap();
+        let mut lock = read_template_lock(target_path).unwrap().unwrap();
+        lock.templates[0]
+            .files
+            .insert(PathBuf::from("old.txt"), sha256(b"old\n"));
+        update_template_lock(target_path, lock.templates[0].clone()).unwrap();
+
+        let diffs = diff_project(target_path).unwrap();
+        assert_eq!(
+            diffs
+                .iter()
+                .map(|diff| (diff.path.to_string_lossy().to_string(), diff.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Cargo.toml".to_string(), FileDiffKind::Removed),
+                ("old.txt".to_string(), FileDiffKind::Added),
+                ("wit/my-component.wit".to_string(), FileDiffKind::Modified),
+            ]
+        );
+        let modified = &diffs[2];
+        assert_eq!(modified.line_stat(), Some((1, 0)));
+        let unified = modified.unified_diff(false);
+        assert!(unified.contains("--- a/wit/my-component.wit"));
+        assert!(unified.contains("+  export extra;"));
+        assert!(diffs[1].unified_diff(false).contains("--- /dev/null"));
+
+        // Without the lock, files which are not generated anymore are unknown
+        assert_eq!(
+            diff_example(&project.example, &project.parameters)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
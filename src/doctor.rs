@@ -0,0 +1,370 @@
+use crate::model::{Example, ExampleKind, GuestLanguage};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+static VERSION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[0-9]+(?:\.[0-9]+)+").unwrap());
+
+/// A tool required for building the components of a guest language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tool {
+    pub name: &'static str,
+    pub executable: &'static str,
+    /// Environment variable pointing to the root of the tool, the executable is searched in its
+    /// `bin` directory instead of PATH
+    pub root_env_var: Option<&'static str>,
+    pub version_args: &'static [&'static str],
+    pub min_version: Option<&'static str>,
+    /// Suggested fix when the tool is missing or outdated
+    pub fix: &'static str,
+}
+
+const CARGO: Tool = Tool {
+    name: "cargo",
+    executable: "cargo",
+    root_env_var: None,
+    version_args: &["--version"],
+    min_version: None,
+    fix: "Install Rust with rustup: https://rustup.rs",
+};
+
+const CARGO_COMPONENT: Tool = Tool {
+    name: "cargo-component",
+    executable: "cargo-component",
+    root_env_var: None,
+    version_args: &["--version"],
+    min_version: Some("0.20.0"),
+    fix: "cargo install --force --locked cargo-component@0.20.0",
+};
+
+const TINYGO: Tool = Tool {
+    name: "tinygo",
+    executable: "tinygo",
+    root_env_var: None,
+    version_args: &["version"],
+    min_version: Some("0.33.0"),
+    fix: "Install TinyGo: https://tinygo.org/getting-started/install/",
+};
+
+const WIT_BINDGEN: Tool = Tool {
+    name: "wit-bindgen",
+    executable: "wit-bindgen",
+    root_env_var: None,
+    version_args: &["--version"],
+    min_version: None,
+    fix: "cargo install --force --locked wit-bindgen-cli",
+};
+
+const WASM_TOOLS: Tool = Tool {
+    name: "wasm-tools",
+    executable: "wasm-tools",
+    root_env_var: None,
+    version_args: &["--version"],
+    min_version: None,
+    fix: "cargo install --force --locked wasm-tools",
+};
+
+const WASI_SDK: Tool = Tool {
+    name: "wasi-sdk",
+    executable: "clang",
+    root_env_var: Some("WASI_SDK_PATH"),
+    version_args: &["--version"],
+    min_version: None,
+    fix: "Install wasi-sdk 25.0 from https://github.com/WebAssembly/wasi-sdk/releases and set WASI_SDK_PATH to its directory",
+};
+
+const ZIG: Tool = Tool {
+    name: "zig",
+    executable: "zig",
+    root_env_var: None,
+    version_args: &["version"],
+    min_version: None,
+    fix: "Install Zig: https://ziglang.org/download/",
+};
+
+const NPM: Tool = Tool {
+    name: "npm",
+    executable: "npm",
+    root_env_var: None,
+    version_args: &["--version"],
+    min_version: None,
+    fix: "Install Node.js and npm: https://nodejs.org/en/download",
+};
+
+const COMPONENTIZE_PY: Tool = Tool {
+    name: "componentize-py",
+    executable: "componentize-py",
+    root_env_var: None,
+    version_args: &["--version"],
+    min_version: Some("0.13.5"),
+    fix: "pip install componentize-py==0.13.5",
+};
+
+const GOLEM_CLI: Tool = Tool {
+    name: "golem-cli",
+    executable: "golem-cli",
+    root_env_var: None,
+    version_args: &["--version"],
+    min_version: Some("1.1.0"),
+    fix: "cargo install --locked golem-cli",
+};
+
+/// Tools required for building the components of the language
+pub fn language_tools(language: GuestLanguage) -> Vec<&'static Tool> {
+    match language {
+        GuestLanguage::Rust => vec![&CARGO, &CARGO_COMPONENT],
+        GuestLanguage::Go => vec![&TINYGO, &WIT_BINDGEN, &WASM_TOOLS],
+        GuestLanguage::C => vec![&WASI_SDK, &WIT_BINDGEN, &WASM_TOOLS],
+        GuestLanguage::Zig => vec![&ZIG],
+        GuestLanguage::JavaScript | GuestLanguage::TypeScript => vec![&NPM],
+        GuestLanguage::Python => vec![&COMPONENTIZE_PY],
+        GuestLanguage::CSharp
+        | GuestLanguage::Swift
+        | GuestLanguage::Grain
+        | GuestLanguage::Scala2 => vec![],
+    }
+}
+
+/// Tools required for building the example, composable app templates and examples using
+/// `golem-cli` in their instructions require `golem-cli` too
+pub fn example_tools(example: &Example) -> Vec<&'static Tool> {
+    let mut tools = language_tools(example.language);
+    let uses_golem_cli = match example.kind {
+        ExampleKind::Standalone => example.instructions.contains("golem-cli"),
+        ExampleKind::ComposableAppCommon { .. } | ExampleKind::ComposableAppComponent { .. } => {
+            true
+        }
+    };
+    if uses_golem_cli {
+        tools.push(&GOLEM_CLI);
+    }
+    tools
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolStatus {
+    Ok,
+    Missing,
+    Outdated {
+        min_version: String,
+    },
+    /// The tool was found, but its version command failed or printed no version
+    UnknownVersion,
+}
+
+impl fmt::Display for ToolStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolStatus::Ok => write!(f, "ok"),
+            ToolStatus::Missing => write!(f, "missing"),
+            ToolStatus::Outdated { min_version } => write!(f, "outdated, requires {min_version}"),
+            ToolStatus::UnknownVersion => write!(f, "unknown version"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCheck {
+    pub tool: &'static Tool,
+    pub path: Option<PathBuf>,
+    pub version: Option<String>,
+    pub status: ToolStatus,
+}
+
+impl ToolCheck {
+    /// Suggested fix, if the tool is not usable
+    pub fn fix(&self) -> Option<&'static str> {
+        match self.status {
+            ToolStatus::Ok => None,
+            ToolStatus::Missing | ToolStatus::Outdated { .. } | ToolStatus::UnknownVersion => {
+                Some(self.tool.fix)
+            }
+        }
+    }
+}
+
+/// Checks the tools using the environment variables provided by `env_var`, so the checks
+/// can be run against a custom PATH
+pub fn check_tools(
+    tools: &[&'static Tool],
+    env_var: impl Fn(&str) -> Option<OsString>,
+) -> Vec<ToolCheck> {
+    tools
+        .iter()
+        .map(|tool| {
+            let path = match tool.root_env_var {
+                Some(root_env_var) => env_var(root_env_var).and_then(|root| {
+                    find_executable(
+                        tool.executable,
+                        &PathBuf::from(root).join("bin").into_os_string(),
+                    )
+                }),
+                None => env_var("PATH").and_then(|path| find_executable(tool.executable, &path)),
+            };
+            let Some(path) = path else {
+                return ToolCheck {
+                    tool,
+                    path: None,
+                    version: None,
+                    status: ToolStatus::Missing,
+                };
+            };
+
+            let version = tool_version(&path, tool.version_args);
+            let status = match (&version, tool.min_version) {
+                (None, _) => ToolStatus::UnknownVersion,
+                (Some(version), Some(min_version))
+                    if parse_version(version) < parse_version(min_version) =>
+                {
+                    ToolStatus::Outdated {
+                        min_version: min_version.to_string(),
+                    }
+                }
+                (Some(_), _) => ToolStatus::Ok,
+            };
+            ToolCheck {
+                tool,
+                path: Some(path),
+                version,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Searches the executable in the directories of a PATH-like value
+pub fn find_executable(name: &str, path: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path)
+        .flat_map(|dir| executable_candidates(&dir, name))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn executable_candidates(dir: &Path, name: &str) -> Vec<PathBuf> {
+    if cfg!(windows) {
+        vec![
+            dir.join(format!("{name}.exe")),
+            dir.join(format!("{name}.cmd")),
+            dir.join(name),
+        ]
+    } else {
+        vec![dir.join(name)]
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+fn tool_version(path: &Path, version_args: &[&str]) -> Option<String> {
+    let output = Command::new(path).args(version_args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let output = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    VERSION_REGEX
+        .find(&output)
+        .map(|version| version.as_str().to_string())
+}
+
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::doctor::{
+        check_tools, find_executable, ToolStatus, CARGO_COMPONENT, COMPONENTIZE_PY, GOLEM_CLI,
+        WASI_SDK,
+    };
+    use std::ffi::OsString;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    fn stub_executable(dir: &Path, name: &str, output: &str) {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\necho \"{output}\"\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    pub fn check_stub_tools() {
+        let stubs = tempfile::tempdir().unwrap();
+        let dir = stubs.path();
+        stub_executable(
+            dir,
+            "cargo-component",
+            "cargo-component 0.20.0 (wasi:040ec2d)",
+        );
+        stub_executable(dir, "componentize-py", "componentize-py 0.13.4");
+        stub_executable(dir, "golem-cli", "golem-cli");
+
+        let checks = check_tools(
+            &[&CARGO_COMPONENT, &COMPONENTIZE_PY, &GOLEM_CLI, &WASI_SDK],
+            |name| (name == "PATH").then(|| dir.as_os_str().to_os_string()),
+        );
+
+        assert_eq!(checks[0].status, ToolStatus::Ok);
+        assert_eq!(checks[0].version.as_deref(), Some("0.20.0"));
+        assert_eq!(
+            checks[0].path.as_deref(),
+            Some(dir.join("cargo-component").as_path())
+        );
+        assert_eq!(
+            checks[1].status,
+            ToolStatus::Outdated {
+                min_version: "0.13.5".to_string()
+            }
+        );
+        assert_eq!(checks[2].status, ToolStatus::UnknownVersion);
+        assert_eq!(checks[3].status, ToolStatus::Missing);
+        assert!(checks[3].fix().unwrap().contains("WASI_SDK_PATH"));
+    }
+
+    #[test]
+    pub fn check_tool_with_root_env_var() {
+        let stubs = tempfile::tempdir().unwrap();
+        let dir = stubs.path();
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+        stub_executable(&dir.join("bin"), "clang", "clang version 19.1.5-wasi-sdk");
+
+        let checks = check_tools(&[&WASI_SDK], |name| {
+            (name == "WASI_SDK_PATH").then(|| dir.as_os_str().to_os_string())
+        });
+
+        assert_eq!(checks[0].status, ToolStatus::Ok);
+        assert_eq!(checks[0].version.as_deref(), Some("19.1.5"));
+    }
+
+    #[test]
+    pub fn find_executable_skips_non_executables() {
+        let (first_stubs, second_stubs) =
+            (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (first, second) = (first_stubs.path(), second_stubs.path());
+        std::fs::write(first.join("npm"), "not executable").unwrap();
+        stub_executable(second, "npm", "10.9.0");
+
+        let path = std::env::join_paths([&first, &second]).unwrap();
+        assert_eq!(find_executable("npm", &path), Some(second.join("npm")));
+        assert_eq!(find_executable("zig", &OsString::from(&path)), None);
+    }
+}
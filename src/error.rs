@@ -0,0 +1,208 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Exit code of the CLI when the `doctor` or `lint` checks found problems
+pub const FAILED_CHECK_EXIT_CODE: i32 = 1;
+
+/// Errors of generating and maintaining projects from the templates
+#[derive(Debug)]
+pub enum Error {
+    /// The requested example, template, group, adapter or partial is not part of the catalog
+    Catalog {
+        /// Template or catalog file the error refers to, if any
+        path: Option<PathBuf>,
+        message: String,
+    },
+    /// Invalid parameters, names or specs provided by the user
+    Validation {
+        /// Spec, lock or project file the error refers to, if any
+        path: Option<PathBuf>,
+        message: String,
+    },
+    /// A generated file already exists and the resolve mode does not allow touching it
+    Conflict {
+        path: PathBuf,
+    },
+    /// An existing file could not be merged with the generated one
+    Merge {
+        path: PathBuf,
+        message: String,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+}
+
+impl Error {
+    pub fn catalog(message: impl Into<String>) -> Error {
+        Error::Catalog {
+            path: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Error {
+        Error::Validation {
+            path: None,
+            message: message.into(),
+        }
+    }
+
+    /// Exit code of the CLI for the error, 1 (`FAILED_CHECK_EXIT_CODE`) and 2 (clap usage errors)
+    /// are not used
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Catalog { .. } => 3,
+            Error::Validation { .. } => 4,
+            Error::Conflict { .. } => 5,
+            Error::Merge { .. } => 6,
+            Error::Io { .. } => 7,
+        }
+    }
+
+    /// Prefixes the message of catalog and validation errors, e.g. with the failing item
+    pub fn context(self, context: impl fmt::Display) -> Error {
+        match self {
+            Error::Catalog { path, message } => Error::Catalog {
+                path,
+                message: format!("{context}: {message}"),
+            },
+            Error::Validation { path, message } => Error::Validation {
+                path,
+                message: format!("{context}: {message}"),
+            },
+            err => err,
+        }
+    }
+
+    /// Attaches the source path to catalog and validation errors which do not have one yet
+    pub fn with_path(self, source: impl AsRef<Path>) -> Error {
+        let source = || Some(source.as_ref().to_path_buf());
+        match self {
+            Error::Catalog {
+                path: None,
+                message,
+            } => Error::Catalog {
+                path: source(),
+                message,
+            },
+            Error::Validation {
+                path: None,
+                message,
+            } => Error::Validation {
+                path: source(),
+                message,
+            },
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Catalog { path, message } | Error::Validation { path, message } => match path {
+                Some(path) => write!(f, "{}: {message}", path.display()),
+                None => write!(f, "{message}"),
+            },
+            Error::Conflict { path } => write!(f, "Target ({}) already exists!", path.display()),
+            Error::Merge { path, message } => {
+                write!(f, "Failed to merge {}: {}", path.display(), message)
+            }
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Attaches the path to IO errors
+pub(crate) trait IoResultExt<T> {
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T>;
+}
+
+impl<T> IoResultExt<T> for io::Result<T> {
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T> {
+        self.map_err(|source| Error::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{Error, IoResultExt, FAILED_CHECK_EXIT_CODE};
+    use std::collections::HashSet;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    pub fn exit_codes_are_distinct() {
+        let errors = [
+            Error::catalog("catalog"),
+            Error::validation("validation"),
+            Error::Conflict {
+                path: PathBuf::from("a"),
+            },
+            Error::Merge {
+                path: PathBuf::from("a"),
+                message: "merge".to_string(),
+            },
+            Error::Io {
+                path: PathBuf::from("a"),
+                source: io::Error::other("io"),
+            },
+        ];
+        let codes = errors
+            .iter()
+            .map(|err| err.exit_code())
+            .collect::<HashSet<_>>();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes
+            .iter()
+            .all(|code| *code > 2 && *code != FAILED_CHECK_EXIT_CODE));
+    }
+
+    #[test]
+    pub fn io_errors_have_paths() {
+        let err = std::fs::read(Path::new("does/not/exist"))
+            .with_path("does/not/exist")
+            .unwrap_err();
+        assert!(matches!(&err, Error::Io { path, .. } if path == Path::new("does/not/exist")));
+        assert!(err.to_string().starts_with("does/not/exist: "));
+    }
+
+    #[test]
+    pub fn catalog_and_validation_errors_have_source_paths() {
+        let err = Error::validation("Invalid package name")
+            .context("Component api")
+            .with_path("specs/app.yaml");
+        assert!(
+            matches!(&err, Error::Validation { path: Some(path), .. } if path == Path::new("specs/app.yaml"))
+        );
+        assert_eq!(
+            err.to_string(),
+            "specs/app.yaml: Component api: Invalid package name"
+        );
+        assert_eq!(
+            Error::catalog("Unknown example")
+                .with_path("a")
+                .with_path("b")
+                .to_string(),
+            "a: Unknown example"
+        );
+    }
+}
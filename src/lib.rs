@@ -1,55 +1,85 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::lock::{template_lock_entry, update_template_lock, TEMPLATE_LOCK_FILE_NAME};
+use crate::metadata::{ExampleMetadata, ExampleMetadataEntry};
 use crate::model::{
-    ComponentName, ComposableAppGroupName, Example, ExampleKind, ExampleMetadata, ExampleName,
-    ExampleParameters, GuestLanguage, PackageName, TargetExistsResolveDecision,
-    TargetExistsResolveMode,
+    Adapter, AdapterCheck, AdapterStatus, AppSpec, ComponentName, ComposableAppGroupName,
+    ConflictResolution, Example, ExampleKind, ExampleName, ExampleParameters, GuestLanguage,
+    GuestLanguageTier, PackageName, TargetConflict, TargetExistsResolveDecision,
+    TargetExistsResolveMode, TemplateParameter,
 };
+use crate::partial::{expand_partial_directives, PARTIALS_DIR, PARTIAL_DIRECTIVE_REGEX};
+use crate::wit::WitBundle;
 use include_dir::{include_dir, Dir, DirEntry};
 use itertools::Itertools;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
 use std::borrow::Cow;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
 use std::path::{Path, PathBuf};
-use std::{fs, io};
 
 #[cfg(feature = "cli")]
 pub mod cli;
+
+pub mod catalog;
+pub mod describe;
+pub mod diff;
+pub mod doctor;
+pub mod error;
+pub mod lint;
+pub mod lock;
+mod metadata;
 pub mod model;
+mod partial;
+pub mod rename;
+pub mod restore;
+pub mod sync;
+#[cfg(test)]
+mod test_fixtures;
+pub mod upgrade;
+pub mod wit;
+pub mod wizard;
 
 static EXAMPLES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/examples");
 static ADAPTERS: Dir<'_> = include_dir!("$OUT_DIR/golem-wit/adapters");
 static WIT: Dir<'_> = include_dir!("$OUT_DIR/golem-wit/wit/deps");
+static WIT_PACKAGES: Lazy<WitBundle> = Lazy::new(|| WitBundle::from_dir(&WIT));
 
-fn all_examples() -> Vec<Example> {
-    let mut result: Vec<Example> = vec![];
-    for entry in EXAMPLES.entries() {
-        if let Some(lang_dir) = entry.as_dir() {
-            let lang_dir_name = lang_dir.path().file_name().unwrap().to_str().unwrap();
-            if let Some(lang) = GuestLanguage::from_string(lang_dir_name) {
-                let adapters_path =
-                    Path::new(lang.tier().name()).join("wasi_snapshot_preview1.wasm");
-
-                for sub_entry in lang_dir.entries() {
-                    if let Some(example_dir) = sub_entry.as_dir() {
-                        let example_dir_name =
-                            example_dir.path().file_name().unwrap().to_str().unwrap();
-                        if example_dir_name != "INSTRUCTIONS" && !example_dir_name.starts_with('.')
-                        {
-                            let example = parse_example(
-                                lang,
-                                lang_dir.path(),
-                                Path::new("INSTRUCTIONS"),
-                                &adapters_path,
-                                example_dir.path(),
-                            );
-                            result.push(example);
-                        }
-                    }
-                }
-            } else {
-                panic!("Invalid guest language name: {lang_dir_name}");
-            }
-        }
-    }
-    result
+/// Metadata of the examples, validated and generated by the build script
+static EXAMPLE_METADATA: Lazy<Vec<ExampleMetadataEntry>> =
+    Lazy::new(|| include!(concat!(env!("OUT_DIR"), "/example_metadata.rs")));
+
+/// File name of the adapters, in generated projects and in the tier directories of `ADAPTERS`
+const ADAPTER_FILE_NAME: &str = "wasi_snapshot_preview1.wasm";
+
+/// Template files with this suffix are replaced by the partial referenced in their contents
+const PARTIAL_FILE_SUFFIX: &str = ".partial";
+
+/// Placeholders replaced by the component name, see `transform`
+const COMPONENT_NAME_PLACEHOLDERS: &[&str] = &[
+    "componentname",
+    "component-name",
+    "ComponentName",
+    "componentName",
+    "component_name",
+];
+
+/// Placeholders replaced by the package name, see `transform`
+const PACKAGE_NAME_PLACEHOLDERS: &[&str] = &[
+    "pack::name",
+    "pa_ck::na_me",
+    "pack:name",
+    "pack_name",
+    "pack-name",
+    "pack/name",
+    "PackName",
+    "pack-ns",
+    "PackNs",
+];
+
+/// Lists every example, including the composable app templates
+pub fn all_examples() -> Vec<Example> {
+    EXAMPLE_METADATA.iter().map(parse_example).collect()
 }
 
 pub fn all_standalone_examples() -> Vec<Example> {
@@ -89,17 +119,10 @@ pub fn all_composable_app_examples(
         match &example.kind {
             ExampleKind::Standalone => continue,
             ExampleKind::ComposableAppCommon { group, .. } => {
-                let common = &mut app_examples(&mut examples, example.language, group).common;
-                if let Some(common) = common {
-                    panic!(
-                        "Multiple common examples were found for {} - {}, example paths: {}, {}",
-                        example.language,
-                        group,
-                        common.example_path.display(),
-                        example.example_path.display()
-                    );
-                }
-                *common = Some(example);
+                // Multiple common templates of a group are rejected by the build script
+                app_examples(&mut examples, example.language, group)
+                    .common
+                    .get_or_insert(example);
             }
             ExampleKind::ComposableAppComponent { group } => {
                 app_examples(&mut examples, example.language, group)
@@ -116,65 +139,473 @@ pub fn instantiate_example(
     example: &Example,
     parameters: &ExampleParameters,
     resolve_mode: TargetExistsResolveMode,
-) -> io::Result<String> {
-    instantiate_directory(
-        &EXAMPLES,
-        &example.example_path,
+) -> Result<String> {
+    let adapter_source = &example.adapter_source;
+    if let Some(adapter_source) = adapter_source {
+        get_adapter_contents(adapter_source)?;
+    }
+    check_template_parameters(example, parameters)?;
+
+    let mut written = instantiate_template_files(example, parameters, resolve_mode)?;
+    if let (Some(adapter_source), Some(adapter_target)) = (&adapter_source, adapter_target(example))
+    {
+        let adapter_path = parameters.target_path.join(&adapter_target);
+        let adapter_dir = adapter_path.parent().unwrap();
+        fs::create_dir_all(adapter_dir).with_path(adapter_dir)?;
+        copy_adapter(adapter_source, &adapter_path)?;
+        written.insert(
+            adapter_target,
+            get_adapter_contents(adapter_source)?.to_vec(),
+        );
+    }
+    for wit_dep in &example.wit_deps {
+        for target_wit_deps in wit_deps_targets(example) {
+            let target = target_wit_deps.join(wit_dep.file_name().unwrap().to_str().unwrap());
+            for (file_name, contents) in copy_all(
+                &WIT,
+                wit_dep,
+                &parameters.target_path.join(&target),
+                TargetExistsResolveMode::MergeOrSkip,
+            )? {
+                written.insert(target.join(file_name), contents);
+            }
+        }
+    }
+    update_template_lock(
         &parameters.target_path,
+        template_lock_entry(example, parameters, hash_contents(&written))?,
+    )?;
+    Ok(render_example_instructions(example, parameters))
+}
+
+/// Path of the adapter in the project, relative to the target path
+pub(crate) fn adapter_target(example: &Example) -> Option<PathBuf> {
+    example.adapter_source.as_ref().map(|_| {
+        example
+            .adapter_target
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("adapters"))
+            .join(example.language.tier().name())
+            .join(ADAPTER_FILE_NAME)
+    })
+}
+
+/// Directories the WIT dependencies are copied to, relative to the target path
+pub(crate) fn wit_deps_targets(example: &Example) -> Vec<PathBuf> {
+    example
+        .wit_deps_targets
+        .clone()
+        .unwrap_or_else(|| vec![Path::new("wit").join("deps")])
+}
+
+/// A file of a template, as it would be generated
+pub(crate) struct TemplateFile {
+    /// Path relative to the example directory
+    pub source: PathBuf,
+    /// Path relative to the target directory
+    pub target: PathBuf,
+    /// Catalog path of the contents, differs from the source for `.partial` files
+    pub contents_source: PathBuf,
+    pub transform: bool,
+}
+
+/// Lists the files of the template, `instantiate_example` writes exactly these files
+pub(crate) fn template_files(
+    example: &Example,
+    parameters: &ExampleParameters,
+) -> Result<Vec<TemplateFile>> {
+    fn collect(
+        dir: &Dir<'_>,
+        example: &Example,
+        parameters: &ExampleParameters,
+        target: &Path,
+        files: &mut Vec<TemplateFile>,
+    ) -> Result<()> {
+        for entry in dir.entries() {
+            let name = entry.path().file_name().unwrap().to_str().unwrap();
+            if example.is_excluded(example_relative_path(example, entry.path()))
+                || name == "metadata.json"
+            {
+                continue;
+            }
+            let name = file_name_transform(name, example, parameters);
+            match entry {
+                DirEntry::Dir(dir) => {
+                    collect(dir, example, parameters, &target.join(&name), files)?;
+                }
+                DirEntry::File(file) => {
+                    let (contents_source, name) = match name.strip_suffix(PARTIAL_FILE_SUFFIX) {
+                        Some(name) => (partial_file_source(file)?, name.to_string()),
+                        None => (file.path().to_path_buf(), name),
+                    };
+                    files.push(TemplateFile {
+                        source: file
+                            .path()
+                            .strip_prefix(&example.example_path)
+                            .unwrap_or(file.path())
+                            .to_path_buf(),
+                        target: target.join(&name),
+                        contents_source,
+                        transform: example.transform && !example.transform_exclude.contains(&name),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = vec![];
+    collect(
+        catalog_dir(&EXAMPLES, &example.example_path)?,
         example,
         parameters,
-        resolve_mode,
+        Path::new(""),
+        &mut files,
     )?;
-    if let Some(adapter_path) = &example.adapter_source {
-        let adapter_dir = {
-            parameters
-                .target_path
-                .join(match &example.adapter_target {
-                    Some(target) => target.clone(),
-                    None => parameters.target_path.join("adapters"),
-                })
-                .join(example.language.tier().name())
-        };
+    Ok(files)
+}
 
-        fs::create_dir_all(&adapter_dir)?;
-        copy(
-            &ADAPTERS,
-            adapter_path,
-            &adapter_dir.join(adapter_path.file_name().unwrap().to_str().unwrap()),
-            TargetExistsResolveMode::MergeOrSkip,
-        )?;
-    }
-    let wit_deps_targets = {
-        match &example.wit_deps_targets {
-            Some(paths) => paths
-                .iter()
-                .map(|path| parameters.target_path.join(path))
-                .collect(),
-            None => vec![parameters.target_path.join("wit").join("deps")],
-        }
-    };
+/// Renders a single template file, selected by its path in the template or in the generated
+/// project, without writing anything
+///
+/// The contents are rendered the same way as by `instantiate_example`. Returns the path of the
+/// file relative to the target path, and its contents.
+pub fn render_example_file(
+    example: &Example,
+    parameters: &ExampleParameters,
+    path: &Path,
+) -> Result<(PathBuf, Vec<u8>)> {
+    check_template_parameters(example, parameters)?;
+    let files = template_files(example, parameters)?;
+    let file = files
+        .iter()
+        .find(|file| file.source == path || file.target == path)
+        .ok_or_else(|| {
+            Error::catalog(format!(
+                "File {} is not generated by example {}, use the describe command to see the generated files",
+                path.display(),
+                example.name
+            ))
+        })?;
+    let contents = render_template_file(example, file, parameters)?;
+    Ok((file.target.clone(), contents.into_owned()))
+}
+
+/// Lists the files `instantiate_example` would create, relative to the target path
+pub fn planned_files(example: &Example, parameters: &ExampleParameters) -> Result<Vec<PathBuf>> {
+    let mut files = template_files(example, parameters)?
+        .into_iter()
+        .map(|file| file.target)
+        .collect::<Vec<_>>();
+    files.extend(adapter_target(example));
+    let wit_deps_targets = wit_deps_targets(example);
     for wit_dep in &example.wit_deps {
+        let Some(dir) = WIT.get_dir(wit_dep) else {
+            continue;
+        };
         for target_wit_deps in &wit_deps_targets {
-            let target = target_wit_deps.join(wit_dep.file_name().unwrap().to_str().unwrap());
-            copy_all(&WIT, wit_dep, &target, TargetExistsResolveMode::MergeOrSkip)?;
+            for file in dir.files() {
+                files.push(
+                    target_wit_deps
+                        .join(wit_dep.file_name().unwrap())
+                        .join(file.path().file_name().unwrap()),
+                );
+            }
         }
     }
-    Ok(render_example_instructions(example, parameters))
+    files.push(PathBuf::from(TEMPLATE_LOCK_FILE_NAME));
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Lists the planned files of `instantiate_example` which already exist in the target directory,
+/// and how they would be resolved, so conflicts can be shown before writing anything
+pub fn target_conflicts(
+    example: &Example,
+    parameters: &ExampleParameters,
+    resolve_mode: TargetExistsResolveMode,
+) -> Result<Vec<TargetConflict>> {
+    let template_files = template_files(example, parameters)?
+        .into_iter()
+        .map(|file| file.target)
+        .collect::<HashSet<_>>();
+    let adapter_contents = example
+        .adapter_source
+        .clone()
+        .and_then(|adapter_source| get_adapter_contents(&adapter_source).ok());
+
+    Ok(planned_files(example, parameters)?
+        .into_iter()
+        .filter_map(|path| {
+            let target = parameters.target_path.join(&path);
+            // The lock file is updated with the entry of the example
+            if !target.is_file() || path == Path::new(TEMPLATE_LOCK_FILE_NAME) {
+                return None;
+            }
+            let resolution = if template_files.contains(&path) {
+                conflict_resolution(&target, resolve_mode)
+            } else if path.file_name().and_then(|name| name.to_str()) == Some(ADAPTER_FILE_NAME)
+                && !path.starts_with("wit")
+            {
+                if adapter_contents.is_some_and(|contents| {
+                    fs::read(&target).is_ok_and(|existing| existing == contents)
+                }) {
+                    return None;
+                }
+                ConflictResolution::Replace
+            } else {
+                // WIT dependencies are always merged into the existing packages
+                ConflictResolution::Skip
+            };
+            Some(TargetConflict { path, resolution })
+        })
+        .collect())
+}
+
+/// JSON schema of the example `metadata.json` files
+pub fn metadata_json_schema() -> schemars::schema::RootSchema {
+    ExampleMetadata::json_schema()
+}
+
+/// Lists the adapters embedded from golem-wit
+pub fn all_adapters() -> Vec<Adapter> {
+    let mut result = vec![];
+    for tier_dir in ADAPTERS.dirs() {
+        let tier_dir_name = tier_dir.path().file_name().unwrap().to_str().unwrap();
+        let Some(tier) = GuestLanguageTier::from_string(tier_dir_name) else {
+            continue;
+        };
+        if let Some(file) = tier_dir.get_file(tier_dir.path().join(ADAPTER_FILE_NAME)) {
+            result.push(Adapter {
+                tier,
+                path: file.path().to_path_buf(),
+                sha256: sha256(file.contents()),
+            });
+        }
+    }
+    result
+}
+
+/// Checks the adapters found in a generated project against the embedded adapters
+pub fn check_adapters(project_path: &Path) -> Result<Vec<AdapterCheck>> {
+    let adapters = all_adapters();
+    let mut result = vec![];
+    for path in find_project_adapters(project_path)? {
+        let tier_dir_name = path
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let Some(tier) = GuestLanguageTier::from_string(tier_dir_name) else {
+            continue;
+        };
+        let sha256 = sha256(&fs::read(&path).with_path(&path)?);
+        let status = if adapters
+            .iter()
+            .any(|adapter| adapter.tier == tier && adapter.sha256 == sha256)
+        {
+            AdapterStatus::UpToDate
+        } else {
+            AdapterStatus::Outdated
+        };
+        result.push(AdapterCheck {
+            path,
+            tier,
+            sha256,
+            status,
+        });
+    }
+    Ok(result)
+}
+
+/// Replaces the outdated adapters of a generated project with the embedded ones
+pub fn refresh_adapters(project_path: &Path) -> Result<Vec<AdapterCheck>> {
+    let mut result = check_adapters(project_path)?;
+    for check in &mut result {
+        if check.status == AdapterStatus::Outdated {
+            let source = Path::new(check.tier.name()).join(ADAPTER_FILE_NAME);
+            copy_adapter(&source, &check.path)?;
+            check.sha256 = sha256(get_adapter_contents(&source)?);
+            check.status = AdapterStatus::Refreshed;
+        }
+    }
+    Ok(result)
+}
+
+fn find_project_adapters(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut result = vec![];
+    for entry in fs::read_dir(path).with_path(path)? {
+        let entry = entry.with_path(path)?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if entry.file_type().with_path(entry.path())?.is_dir() {
+            if !name.starts_with('.') && name != "node_modules" && name != "target" {
+                result.extend(find_project_adapters(&entry.path())?);
+            }
+        } else if name == ADAPTER_FILE_NAME {
+            result.push(entry.path());
+        }
+    }
+    result.sort();
+    Ok(result)
+}
+
+/// Copies the adapter, replacing the target if it differs from the embedded one
+fn copy_adapter(source: &Path, target: &Path) -> Result<()> {
+    let contents = get_adapter_contents(source)?;
+    if target.exists() && fs::read(target).with_path(target)? == contents {
+        return Ok(());
+    }
+    fs::write(target, contents).with_path(target)
+}
+
+pub(crate) fn get_adapter_contents(source: &Path) -> Result<&'static [u8]> {
+    ADAPTERS
+        .get_file(source)
+        .map(|file| file.contents())
+        .ok_or_else(|| {
+            Error::catalog(format!(
+                "Adapter {} is not embedded, available adapters: {}",
+                source.display(),
+                all_adapters()
+                    .iter()
+                    .map(|adapter| adapter.path.display())
+                    .join(", ")
+            ))
+        })
+}
+
+pub(crate) fn sha256(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// Finds an example of the catalog by name, including the composable app templates
+pub fn find_example_by_name(name: &ExampleName) -> Result<Example> {
+    all_examples()
+        .into_iter()
+        .find(|example| &example.name == name)
+        .ok_or_else(|| {
+            Error::catalog(format!(
+                "Unknown example {name}. Use the list-examples command to see the available examples."
+            ))
+        })
+}
+
+/// Selects the common and component templates of a composable app group
+///
+/// Without a group, the group containing the template is used, or the default group if no
+/// template is selected. The template can be omitted if the group has only one component template.
+pub fn find_composable_app_example(
+    language: GuestLanguage,
+    group: Option<&ComposableAppGroupName>,
+    template: Option<&ExampleName>,
+) -> Result<(Option<Example>, Example)> {
+    let mut groups = all_composable_app_examples()
+        .remove(&language)
+        .ok_or_else(|| {
+            Error::catalog(format!(
+                "No composable app templates found for language {}, available languages: {}",
+                language.id(),
+                all_composable_app_examples()
+                    .keys()
+                    .map(|language| language.id())
+                    .join(", ")
+            ))
+        })?;
+    let available_groups = groups.keys().map(|group| group.to_string()).join(", ");
+
+    let group = match (group, template) {
+        (Some(group), _) => group.clone(),
+        (None, Some(template)) => groups
+            .iter()
+            .find(|(_, examples)| {
+                examples
+                    .components
+                    .iter()
+                    .any(|example| &example.name == template)
+            })
+            .map(|(group, _)| group.clone())
+            .unwrap_or_default(),
+        (None, None) => ComposableAppGroupName::default(),
+    };
+    let examples = groups.remove(&group).ok_or_else(|| {
+        Error::catalog(format!(
+            "Unknown group {} for language {}, available groups: {}",
+            group,
+            language.id(),
+            available_groups
+        ))
+    })?;
+    let available_templates = examples
+        .components
+        .iter()
+        .map(|example| example.name.to_string())
+        .join(", ");
+
+    let component = match template {
+        Some(template) => examples
+            .components
+            .into_iter()
+            .find(|example| &example.name == template)
+            .ok_or_else(|| {
+                Error::catalog(format!(
+                    "Unknown component template {} in group {} for language {}, available templates: {}",
+                    template,
+                    group,
+                    language.id(),
+                    available_templates
+                ))
+            })?,
+        None => {
+            if examples.components.len() != 1 {
+                return Err(Error::validation(format!(
+                    "Group {} for language {} has {} component templates, select one of them: {}",
+                    group,
+                    language.id(),
+                    examples.components.len(),
+                    available_templates
+                )));
+            }
+            examples.components.into_iter().next().unwrap()
+        }
+    };
+
+    Ok((examples.common, component))
 }
 
 pub fn add_component_by_example(
     common_example: Option<&Example>,
     component_example: &Example,
     target_path: &Path,
+    component_name: &ComponentName,
     package_name: &PackageName,
-) -> io::Result<()> {
+    template_parameters: &BTreeMap<String, String>,
+) -> Result<String> {
     let parameters = ExampleParameters {
-        component_name: ComponentName::new(package_name.to_string_with_colon()),
+        component_name: component_name.clone(),
         package_name: package_name.clone(),
         target_path: target_path.into(),
+        template_parameters: template_parameters.clone(),
     };
 
+    check_template_parameters(component_example, &parameters)?;
+
     if let Some(common_example) = common_example {
+        // The common template only gets the parameters it declares
+        let parameters = ExampleParameters {
+            template_parameters: template_parameters
+                .iter()
+                .filter(|(name, _)| {
+                    common_example
+                        .parameters
+                        .iter()
+                        .any(|parameter| &parameter.name == *name)
+                })
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            ..parameters.clone()
+        };
         let skip = {
             if let ExampleKind::ComposableAppCommon {
                 skip_if_exists: Some(file),
@@ -200,132 +631,292 @@ pub fn add_component_by_example(
         component_example,
         &parameters,
         TargetExistsResolveMode::MergeOrFail,
-    )?;
+    )
+}
 
-    Ok(())
+/// Generates a composable application from the spec into `target_path`, which must not exist or
+/// must be empty
+///
+/// The components are added in order, the common template of each group is instantiated once.
+/// The application is generated into a staging directory next to the target, which is only
+/// moved into place when every component was added, so a failed run leaves nothing behind.
+/// Returns the combined instructions of the components.
+pub fn instantiate_app(spec: &AppSpec, target_path: &Path) -> Result<String> {
+    let mut components = vec![];
+    let mut component_names = HashSet::new();
+    let mut package_names = HashSet::new();
+    for (index, component) in spec.components.iter().enumerate() {
+        let context = format!("Invalid component #{} ({})", index + 1, component.name);
+        let invalid = |err: String| Error::validation(err).context(&context);
+        let package_name = PackageName::from_string(&component.name).ok_or_else(|| {
+            invalid("the name must be a package name in namespace:name format".to_string())
+        })?;
+        if !package_names.insert(package_name.to_string_with_colon()) {
+            return Err(invalid(
+                "the name is used by multiple components".to_string(),
+            ));
+        }
+        let component_name = match &component.component_name {
+            Some(component_name) => ComponentName::from_string(component_name).map_err(invalid)?,
+            None => ComponentName::from_package_name(&package_name),
+        };
+        if !component_names.insert(component_name.to_kebab_case()) {
+            return Err(invalid(format!(
+                "the component name {component_name} is used by multiple components"
+            )));
+        }
+        let language = GuestLanguage::from_string(&component.language)
+            .ok_or_else(|| invalid(format!("unknown language {}", component.language)))?;
+        let (common, example) = find_composable_app_example(
+            language,
+            component.group.as_ref(),
+            component.template.as_ref(),
+        )
+        .map_err(|err| err.context(&context))?;
+        let parameters = ExampleParameters {
+            component_name,
+            package_name,
+            target_path: target_path.to_path_buf(),
+            template_parameters: component.parameters.clone(),
+        };
+        check_template_parameters(&example, &parameters).map_err(|err| err.context(&context))?;
+        components.push((parameters, language, common, example));
+    }
+
+    let target_path = &std::path::absolute(target_path).with_path(target_path)?;
+    if target_path.exists()
+        && fs::read_dir(target_path)
+            .with_path(target_path)?
+            .next()
+            .is_some()
+    {
+        return Err(Error::Conflict {
+            path: target_path.clone(),
+        });
+    }
+    let staging_path = target_path.with_file_name(format!(
+        ".{}.staging-{}",
+        target_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        nanoid::nanoid!(10)
+    ));
+
+    let add_components = || -> Result<String> {
+        let mut instantiated_commons = HashSet::new();
+        let mut instructions = vec![];
+        for (parameters, language, common, example) in &components {
+            let common = common
+                .as_ref()
+                .filter(|common| instantiated_commons.insert(common.name.clone()));
+            let component_instructions = add_component_by_example(
+                common,
+                example,
+                &staging_path,
+                &parameters.component_name,
+                &parameters.package_name,
+                &parameters.template_parameters,
+            )?;
+            instructions.push(format!(
+                "Added component {} ({}, {})",
+                parameters.component_name,
+                language.name(),
+                example.name
+            ));
+            if !component_instructions.trim().is_empty() {
+                instructions.push(component_instructions);
+            }
+        }
+        Ok(instructions.join("\n"))
+    };
+
+    match add_components() {
+        Ok(instructions) => {
+            if target_path.exists() {
+                for entry in fs::read_dir(&staging_path).with_path(&staging_path)? {
+                    let entry = entry.with_path(&staging_path)?;
+                    let target = target_path.join(entry.file_name());
+                    fs::rename(entry.path(), &target).with_path(&target)?;
+                }
+                fs::remove_dir(&staging_path).with_path(&staging_path)?;
+            } else {
+                fs::rename(&staging_path, target_path).with_path(target_path)?;
+            }
+            Ok(instructions)
+        }
+        Err(err) => {
+            // The original error is more relevant than failing to clean up the staging directory
+            if staging_path.exists() {
+                let _ = fs::remove_dir_all(&staging_path);
+            }
+            Err(err)
+        }
+    }
 }
 
 pub fn render_example_instructions(example: &Example, parameters: &ExampleParameters) -> String {
-    transform(&example.instructions, parameters)
+    transform(&example.instructions, example, parameters)
 }
 
-fn instantiate_directory(
-    catalog: &Dir<'_>,
-    source: &Path,
-    target: &Path,
+/// Writes the template files into the target directory, resolving the existing files with the
+/// resolve mode, returns the generated contents by path relative to the target path, see
+/// `write_contents`
+fn instantiate_template_files(
     example: &Example,
     parameters: &ExampleParameters,
     resolve_mode: TargetExistsResolveMode,
-) -> io::Result<()> {
-    fs::create_dir_all(target)?;
-    for entry in catalog
-        .get_dir(source)
-        .unwrap_or_else(|| panic!("Could not find entry {source:?}"))
-        .entries()
-    {
-        let name = entry.path().file_name().unwrap().to_str().unwrap();
-        if !example.exclude.contains(name) && (name != "metadata.json") {
-            let name = file_name_transform(name, parameters);
-            match entry {
-                DirEntry::Dir(dir) => {
-                    instantiate_directory(
-                        catalog,
-                        dir.path(),
-                        &target.join(&name),
-                        example,
-                        parameters,
-                        resolve_mode,
-                    )?;
-                }
-                DirEntry::File(file) => {
-                    instantiate_file(
-                        catalog,
-                        file.path(),
-                        &target.join(&name),
-                        parameters,
-                        example.transform && !example.transform_exclude.contains(&name),
-                        resolve_mode,
-                    )?;
-                }
-            }
+) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut written = BTreeMap::new();
+    fs::create_dir_all(&parameters.target_path).with_path(&parameters.target_path)?;
+    for file in template_files(example, parameters)? {
+        let target = parameters.target_path.join(&file.target);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_path(parent)?;
+        }
+        let contents = render_template_file(example, &file, parameters)?;
+        if let Some(contents) = write_contents(contents, &target, resolve_mode)? {
+            written.insert(file.target, contents);
         }
     }
-    Ok(())
+    Ok(written)
 }
 
-fn instantiate_file(
-    catalog: &Dir<'_>,
-    source: &Path,
+/// Writes the contents with the resolve mode, returns the contents if they were written (or merged
+/// into the existing file), None if the existing file was kept and differs from the contents
+///
+/// Merged files return the generated contents, not the merge result, as the lock records the hash
+/// of the generated contents together with them as the base of later upgrades.
+fn write_contents(
+    contents: Cow<'_, [u8]>,
     target: &Path,
-    parameters: &ExampleParameters,
-    transform_contents: bool,
     resolve_mode: TargetExistsResolveMode,
-) -> io::Result<()> {
-    match get_resolved_contents(catalog, source, target, resolve_mode)? {
-        Some(contents) => {
-            if transform_contents {
-                fs::write(
-                    target,
-                    transform(
-                        std::str::from_utf8(contents.as_ref()).map_err(|err| {
-                            io::Error::other(format!(
-                                "Failed to decode as utf8, source: {}, err: {}",
-                                source.display(),
-                                err
-                            ))
-                        })?,
-                        parameters,
-                    ),
-                )
-            } else {
-                fs::write(target, contents)
-            }
+) -> Result<Option<Vec<u8>>> {
+    match resolve_contents(contents.clone(), target, resolve_mode)? {
+        Some(resolved) => {
+            fs::write(target, &resolved).with_path(target)?;
+            Ok(Some(contents.into_owned()))
         }
-        None => Ok(()),
+        None => Ok(fs::read(target)
+            .ok()
+            .filter(|existing| existing.as_slice() == contents.as_ref())),
     }
 }
 
-fn copy(
-    catalog: &Dir<'_>,
+/// SHA-256 of the contents, by path
+pub(crate) fn hash_contents(contents: &BTreeMap<PathBuf, Vec<u8>>) -> BTreeMap<PathBuf, String> {
+    contents
+        .iter()
+        .map(|(path, contents)| (path.clone(), sha256(contents)))
+        .collect()
+}
+
+fn catalog_dir<'a>(catalog: &'a Dir<'a>, path: &Path) -> Result<&'a Dir<'a>> {
+    catalog
+        .get_dir(path)
+        .ok_or_else(|| Error::catalog("Could not find dir in catalog").with_path(path))
+}
+
+/// Path of a catalog entry relative to the example directory
+fn example_relative_path<'a>(example: &Example, path: &'a Path) -> &'a Path {
+    path.strip_prefix(&example.example_path).unwrap_or(path)
+}
+
+/// Contents of a template file as generated, after expanding the partials and applying the
+/// template rules
+pub(crate) fn render_file<'a>(
+    catalog: &'a Dir<'a>,
     source: &Path,
-    target: &Path,
-    resolve_mode: TargetExistsResolveMode,
-) -> io::Result<()> {
-    match get_resolved_contents(catalog, source, target, resolve_mode)? {
-        Some(contents) => fs::write(target, contents),
-        None => Ok(()),
+    example: &Example,
+    parameters: &ExampleParameters,
+    transform_contents: bool,
+) -> Result<Cow<'a, [u8]>> {
+    let contents = expand_partials(catalog, source, get_contents(catalog, source)?)?;
+    if !transform_contents {
+        return Ok(contents);
+    }
+    let contents = std::str::from_utf8(contents.as_ref()).map_err(|err| {
+        Error::catalog(format!("Failed to decode as utf8: {err}")).with_path(source)
+    })?;
+    Ok(Cow::Owned(
+        transform(contents, example, parameters).into_bytes(),
+    ))
+}
+
+/// Contents of the template file as `instantiate_example` would generate it
+pub(crate) fn render_template_file(
+    example: &Example,
+    file: &TemplateFile,
+    parameters: &ExampleParameters,
+) -> Result<Cow<'static, [u8]>> {
+    render_file(
+        &EXAMPLES,
+        &file.contents_source,
+        example,
+        parameters,
+        file.transform,
+    )
+}
+
+fn partial_file_source(file: &include_dir::File<'_>) -> Result<PathBuf> {
+    let partial = file.contents_utf8().map(str::trim).ok_or_else(|| {
+        Error::catalog("Failed to decode partial reference as utf8").with_path(file.path())
+    })?;
+    Ok(Path::new(PARTIALS_DIR).join(partial))
+}
+
+fn expand_partials<'a>(
+    catalog: &Dir<'a>,
+    source: &Path,
+    contents: &'a [u8],
+) -> Result<Cow<'a, [u8]>> {
+    match std::str::from_utf8(contents) {
+        Ok(str) if PARTIAL_DIRECTIVE_REGEX.is_match(str) => {
+            let read_partial = |path: &Path| {
+                catalog
+                    .get_file(path)
+                    .and_then(|file| file.contents_utf8())
+                    .map(str::to_string)
+            };
+            Ok(Cow::Owned(
+                expand_partial_directives(source, str, &read_partial)
+                    .map_err(|err| Error::catalog(err).with_path(source))?
+                    .into_bytes(),
+            ))
+        }
+        _ => Ok(Cow::Borrowed(contents)),
     }
 }
 
+/// Copies the files of the catalog directory, returns the generated contents by file name, see
+/// `write_contents`
 fn copy_all(
     catalog: &Dir<'_>,
     source_path: &Path,
     target_path: &Path,
     resolve_mode: TargetExistsResolveMode,
-) -> io::Result<()> {
-    let source_dir = catalog.get_dir(source_path).ok_or_else(|| {
-        io::Error::other(format!(
-            "Could not find dir {} in catalog",
-            source_path.display()
-        ))
-    })?;
+) -> Result<BTreeMap<String, Vec<u8>>> {
+    let source_dir = catalog_dir(catalog, source_path)?;
 
-    fs::create_dir_all(target_path)?;
+    fs::create_dir_all(target_path).with_path(target_path)?;
 
+    let mut written = BTreeMap::new();
     for file in source_dir.files() {
-        copy(
-            catalog,
-            file.path(),
-            &target_path.join(file.path().file_name().unwrap().to_str().unwrap()),
-            resolve_mode,
-        )?;
+        let file_name = file.path().file_name().unwrap().to_str().unwrap();
+        let contents = Cow::Borrowed(get_contents(catalog, file.path())?);
+        if let Some(contents) =
+            write_contents(contents, &target_path.join(file_name), resolve_mode)?
+        {
+            written.insert(file_name.to_string(), contents);
+        }
     }
 
-    Ok(())
+    Ok(written)
 }
 
-fn transform(str: impl AsRef<str>, parameters: &ExampleParameters) -> String {
-    str.as_ref()
+fn transform(str: impl AsRef<str>, example: &Example, parameters: &ExampleParameters) -> String {
+    let str = str
+        .as_ref()
         .replace("componentname", parameters.component_name.as_str())
         .replace("component-name", &parameters.component_name.to_kebab_case())
         .replace("ComponentName", &parameters.component_name.to_pascal_case())
@@ -342,55 +933,99 @@ fn transform(str: impl AsRef<str>, parameters: &ExampleParameters) -> String {
         .replace("pack/name", &parameters.package_name.to_string_with_slash())
         .replace("PackName", &parameters.package_name.to_pascal_case())
         .replace("pack-ns", &parameters.package_name.namespace())
-        .replace("PackNs", &parameters.package_name.namespace_title_case())
+        .replace("PackNs", &parameters.package_name.namespace_title_case());
+
+    example.parameters.iter().fold(str, |str, parameter| {
+        match template_parameter_value(parameter, parameters) {
+            Some(value) => str.replace(&parameter.placeholder, value),
+            None => str,
+        }
+    })
+}
+
+/// Same as `transform`, except `componentname` is replaced by the kebab case of the component
+/// name, as the raw name may contain a `:`, which is not valid in file names on every platform
+fn file_name_transform(
+    str: impl AsRef<str>,
+    example: &Example,
+    parameters: &ExampleParameters,
+) -> String {
+    let str = str
+        .as_ref()
+        .replace("componentname", &parameters.component_name.to_kebab_case());
+    transform(str, example, parameters).replace("Cargo.toml._", "Cargo.toml") // HACK because cargo package ignores every subdirectory containing a Cargo.toml
 }
 
-fn file_name_transform(str: impl AsRef<str>, parameters: &ExampleParameters) -> String {
-    transform(str, parameters).replace("Cargo.toml._", "Cargo.toml") // HACK because cargo package ignores every subdirectory containing a Cargo.toml
+/// The value of a declared template parameter, falling back to its default
+fn template_parameter_value<'a>(
+    parameter: &'a TemplateParameter,
+    parameters: &'a ExampleParameters,
+) -> Option<&'a str> {
+    parameters
+        .template_parameters
+        .get(&parameter.name)
+        .or(parameter.default.as_ref())
+        .map(|value| value.as_str())
+}
+
+/// Checks that every declared template parameter without a default has a value, and that no
+/// undeclared parameters are provided
+pub fn check_template_parameters(example: &Example, parameters: &ExampleParameters) -> Result<()> {
+    for name in parameters.template_parameters.keys() {
+        if !example
+            .parameters
+            .iter()
+            .any(|parameter| &parameter.name == name)
+        {
+            return Err(Error::validation(format!(
+                "Unknown template parameter {} for example {}, available parameters: [{}]",
+                name,
+                example.name,
+                example
+                    .parameters
+                    .iter()
+                    .map(|parameter| &parameter.name)
+                    .join(", ")
+            )));
+        }
+    }
+    for parameter in &example.parameters {
+        if template_parameter_value(parameter, parameters).is_none() {
+            return Err(Error::validation(format!(
+                "Missing value for template parameter {} ({}) of example {}",
+                parameter.name, parameter.description, example.name
+            )));
+        }
+    }
+    Ok(())
 }
 
 fn check_target(
     target: &Path,
     resolve_mode: TargetExistsResolveMode,
-) -> io::Result<Option<TargetExistsResolveDecision>> {
+) -> Result<Option<TargetExistsResolveDecision>> {
     if !target.exists() {
         return Ok(None);
     }
 
-    let get_merge = || -> io::Result<Option<TargetExistsResolveDecision>> {
-        let file_name = target
-            .file_name()
-            .ok_or_else(|| {
-                io::Error::other(format!(
-                    "Failed to get file name for target: {}",
-                    target.display()
-                ))
-            })
-            .and_then(|file_name| {
-                file_name.to_str().ok_or_else(|| {
-                    io::Error::other(format!(
-                        "Failed to convert file name to string: {}",
-                        file_name.to_string_lossy()
-                    ))
-                })
-            })?;
-
-        match file_name {
-            ".gitignore" => {
+    let get_merge = || -> Result<Option<TargetExistsResolveDecision>> {
+        match target.file_name().and_then(|file_name| file_name.to_str()) {
+            Some(".gitignore") => {
                 let target = target.to_path_buf();
-                let current_content = fs::read_to_string(&target)?;
+                let current_content = fs::read_to_string(&target).with_path(&target)?;
                 Ok(Some(TargetExistsResolveDecision::Merge(Box::new(
-                    move |new_content: &[u8]| -> io::Result<Vec<u8>> {
+                    move |new_content: &[u8]| -> Result<Vec<u8>> {
                         Ok(current_content
                             .lines()
                             .chain(
-                                std::str::from_utf8(new_content).map_err(|err| {
-                                    io::Error::other(format!(
-                                        "Failed to decode new content for merge as utf8, target: {}, err: {}",
-                                        target.display(),
-                                        err
-                                    ))
-                                })?.lines(),
+                                std::str::from_utf8(new_content)
+                                    .map_err(|err| Error::Merge {
+                                        path: target.clone(),
+                                        message: format!(
+                                            "failed to decode new content as utf8: {err}"
+                                        ),
+                                    })?
+                                    .lines(),
                             )
                             .collect::<BTreeSet<&str>>()
                             .iter()
@@ -404,10 +1039,9 @@ fn check_target(
     };
 
     let target_already_exists = || {
-        Err(io::Error::other(format!(
-            "Target ({}) already exists!",
-            target.display()
-        )))
+        Err(Error::Conflict {
+            path: target.to_path_buf(),
+        })
     };
 
     match resolve_mode {
@@ -421,98 +1055,97 @@ fn check_target(
             Some(merge) => Ok(Some(merge)),
             None => target_already_exists(),
         },
+        TargetExistsResolveMode::Backup => Ok(Some(TargetExistsResolveDecision::Backup)),
     }
 }
 
-fn get_contents<'a>(catalog: &Dir<'a>, source: &'a Path) -> io::Result<&'a [u8]> {
+/// Files merged instead of replaced by the merge resolve modes, has to match `check_target`
+fn is_mergeable(target: &Path) -> bool {
+    target.file_name().and_then(|name| name.to_str()) == Some(".gitignore")
+}
+
+fn conflict_resolution(target: &Path, resolve_mode: TargetExistsResolveMode) -> ConflictResolution {
+    match resolve_mode {
+        TargetExistsResolveMode::Skip => ConflictResolution::Skip,
+        TargetExistsResolveMode::MergeOrSkip if is_mergeable(target) => ConflictResolution::Merge,
+        TargetExistsResolveMode::MergeOrSkip => ConflictResolution::Skip,
+        TargetExistsResolveMode::Fail => ConflictResolution::Fail,
+        TargetExistsResolveMode::MergeOrFail if is_mergeable(target) => ConflictResolution::Merge,
+        TargetExistsResolveMode::MergeOrFail => ConflictResolution::Fail,
+        TargetExistsResolveMode::Backup => ConflictResolution::Backup,
+    }
+}
+
+/// Renames the file to the first free `<file name>.bak`, `<file name>.bak.1`, ... path
+fn backup_file(target: &Path) -> Result<PathBuf> {
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| Error::validation("Failed to get file name for target").with_path(target))?
+        .to_string_lossy()
+        .to_string();
+    let backup = (0..)
+        .map(|index| match index {
+            0 => target.with_file_name(format!("{file_name}.bak")),
+            index => target.with_file_name(format!("{file_name}.bak.{index}")),
+        })
+        .find(|backup| !backup.exists())
+        .unwrap();
+    fs::rename(target, &backup).with_path(target)?;
+    Ok(backup)
+}
+
+fn get_contents<'a>(catalog: &'a Dir<'a>, source: &Path) -> Result<&'a [u8]> {
     Ok(catalog
         .get_file(source)
-        .ok_or_else(|| io::Error::other(format!("Could not find entry {}", source.display())))?
+        .ok_or_else(|| Error::catalog("Could not find entry in catalog").with_path(source))?
         .contents())
 }
 
-fn get_resolved_contents<'a>(
-    catalog: &Dir<'a>,
-    source: &'a Path,
-    target: &'a Path,
+fn resolve_contents<'a>(
+    contents: Cow<'a, [u8]>,
+    target: &Path,
     resolve_mode: TargetExistsResolveMode,
-) -> io::Result<Option<Cow<'a, [u8]>>> {
+) -> Result<Option<Cow<'a, [u8]>>> {
     match check_target(target, resolve_mode)? {
-        None => Ok(Some(Cow::Borrowed(get_contents(catalog, source)?))),
+        None => Ok(Some(contents)),
         Some(TargetExistsResolveDecision::Skip) => Ok(None),
-        Some(TargetExistsResolveDecision::Merge(merge)) => {
-            Ok(Some(Cow::Owned(merge(get_contents(catalog, source)?)?)))
+        Some(TargetExistsResolveDecision::Merge(merge)) => Ok(Some(Cow::Owned(merge(&contents)?))),
+        Some(TargetExistsResolveDecision::Backup) => {
+            backup_file(target)?;
+            Ok(Some(contents))
         }
     }
 }
 
-fn parse_example(
-    lang: GuestLanguage,
-    lang_path: &Path,
-    default_instructions_file_name: &Path,
-    adapters_path: &Path,
-    example_root: &Path,
-) -> Example {
-    let raw_metadata = EXAMPLES
-        .get_file(example_root.join("metadata.json"))
-        .expect("Failed to read metadata JSON")
-        .contents();
-    let metadata = serde_json::from_slice::<ExampleMetadata>(raw_metadata)
-        .expect("Failed to parse metadata JSON");
+fn parse_example(entry: &ExampleMetadataEntry) -> Example {
+    let metadata = entry.metadata.clone();
+    let example_root = Path::new(entry.language_dir).join(entry.example_dir);
 
     let kind = match (metadata.app_common_group, metadata.app_component_group) {
-        (None, None) => ExampleKind::Standalone,
-        (Some(group), None) => ExampleKind::ComposableAppCommon {
+        (Some(group), _) => ExampleKind::ComposableAppCommon {
             group: ComposableAppGroupName::from_string(group),
             skip_if_exists: metadata.app_common_skip_if_exists.map(PathBuf::from),
         },
         (None, Some(group)) => ExampleKind::ComposableAppComponent {
             group: ComposableAppGroupName::from_string(group),
         },
-        (Some(_), Some(_)) => panic!(
-            "Only one of appCommonGroup and appComponentGroup can be specified, example root: {}",
-            example_root.display()
-        ),
+        (None, None) => ExampleKind::Standalone,
     };
 
-    let instructions = match &kind {
-        ExampleKind::Standalone => {
-            let instructions_path = match metadata.instructions {
-                Some(instructions_file_name) => lang_path.join(instructions_file_name),
-                None => lang_path.join(default_instructions_file_name),
-            };
+    let name = ExampleName::from_string(entry.example_dir);
 
-            let raw_instructions = EXAMPLES
-                .get_file(instructions_path)
-                .expect("Failed to read instructions")
-                .contents();
-
-            String::from_utf8(raw_instructions.to_vec()).expect("Failed to decode instructions")
-        }
-        ExampleKind::ComposableAppCommon { .. } => "".to_string(),
-        ExampleKind::ComposableAppComponent { .. } => "".to_string(),
-    };
+    let exclude: HashSet<String> = metadata.exclude.iter().flatten().cloned().collect();
 
-    let name = ExampleName::from_string(example_root.file_name().unwrap().to_str().unwrap());
+    let declared_wit_deps = resolved_wit_deps(&entry.declared_wit_deps);
 
-    let mut wit_deps: Vec<PathBuf> = vec![];
-    if metadata.requires_golem_host_wit.unwrap_or(false) {
-        wit_deps.push(Path::new("golem").to_path_buf());
-        wit_deps.push(Path::new("golem-1.1").to_path_buf());
-        wit_deps.push(Path::new("wasm-rpc").to_path_buf());
-    }
-    if metadata.requires_wasi.unwrap_or(false) {
-        wit_deps.push(Path::new("blobstore").to_path_buf());
-        wit_deps.push(Path::new("cli").to_path_buf());
-        wit_deps.push(Path::new("clocks").to_path_buf());
-        wit_deps.push(Path::new("filesystem").to_path_buf());
-        wit_deps.push(Path::new("http").to_path_buf());
-        wit_deps.push(Path::new("io").to_path_buf());
-        wit_deps.push(Path::new("keyvalue").to_path_buf());
-        wit_deps.push(Path::new("logging").to_path_buf());
-        wit_deps.push(Path::new("random").to_path_buf());
-        wit_deps.push(Path::new("sockets").to_path_buf());
+    // Component templates of composable apps use the WIT dependencies provided by the common
+    // template, other examples use the declared dependencies if any, the lints warn if they differ
+    // from the inferred ones
+    let wit_deps = match &kind {
+        ExampleKind::ComposableAppComponent { .. } => declared_wit_deps,
+        _ => declared_wit_deps.or(resolved_wit_deps(&entry.inferred_wit_deps)),
     }
+    .unwrap_or_default();
 
     let requires_adapter = metadata
         .requires_adapter
@@ -521,32 +1154,328 @@ fn parse_example(
     Example {
         name,
         kind,
-        language: lang,
+        language: entry.language,
         description: metadata.description,
-        example_path: example_root.to_path_buf(),
-        instructions,
+        tags: metadata.tags.unwrap_or_default(),
+        example_path: example_root,
+        instructions: entry.instructions.to_string(),
         adapter_source: {
             if requires_adapter {
-                Some(adapters_path.to_path_buf())
+                Some(Path::new(entry.language.tier().name()).join(ADAPTER_FILE_NAME))
             } else {
                 None
             }
         },
         adapter_target: metadata.adapter_target.map(PathBuf::from),
-        wit_deps,
+        wit_deps: wit_deps.into_iter().collect(),
         wit_deps_targets: metadata
             .wit_deps_paths
             .map(|dirs| dirs.iter().map(PathBuf::from).collect()),
-        exclude: metadata
-            .exclude
-            .unwrap_or_default()
-            .iter()
-            .cloned()
-            .collect(),
+        exclude,
         transform_exclude: metadata
             .transform_exclude
             .map(|te| te.iter().cloned().collect())
             .unwrap_or_default(),
         transform: metadata.transform.unwrap_or(true),
+        parameters: metadata.parameters.unwrap_or_default(),
+    }
+}
+
+/// WIT dependencies declared in the metadata of the example, resolved by the build script
+pub(crate) fn declared_wit_deps(example: &Example) -> Option<BTreeSet<PathBuf>> {
+    metadata_entry(example).and_then(|entry| resolved_wit_deps(&entry.declared_wit_deps))
+}
+
+/// WIT dependencies referenced from the WIT files of the example, resolved by the build script
+pub(crate) fn inferred_wit_deps(example: &Example) -> Option<BTreeSet<PathBuf>> {
+    metadata_entry(example).and_then(|entry| resolved_wit_deps(&entry.inferred_wit_deps))
+}
+
+fn metadata_entry(example: &Example) -> Option<&'static ExampleMetadataEntry> {
+    EXAMPLE_METADATA
+        .iter()
+        .find(|entry| Path::new(entry.language_dir).join(entry.example_dir) == example.example_path)
+}
+
+fn resolved_wit_deps(paths: &Option<Vec<&'static str>>) -> Option<BTreeSet<PathBuf>> {
+    paths
+        .as_ref()
+        .map(|paths| paths.iter().map(PathBuf::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::model::{
+        AppComponentSpec, AppSpec, ComposableAppGroupName, ConflictResolution, ExampleKind,
+        ExampleName, GuestLanguage, GuestLanguageTier, TargetConflict, TargetExistsResolveMode,
+        TemplateParameter, WitPackageName,
+    };
+    use crate::test_fixtures::{example, parameters, TestProject};
+    use crate::{
+        all_adapters, check_template_parameters, expand_partials, find_composable_app_example,
+        find_example_by_name, instantiate_app, instantiate_example, planned_files,
+        render_example_file, target_conflicts, transform, EXAMPLES, WIT_PACKAGES,
+    };
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    pub fn expand_partial_directive() {
+        let source = Path::new("ts/ts-default/.gitignore");
+        let contents = EXAMPLES.get_file(source).unwrap().contents();
+        let expanded = expand_partials(&EXAMPLES, source, contents).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&expanded).unwrap(),
+            "node_modules/\nout/\nsrc/generated/\nwit-generated/\n"
+        );
+    }
+
+    #[test]
+    pub fn expand_without_partial_directive() {
+        let source = Path::new("rust/INSTRUCTIONS");
+        let contents = EXAMPLES.get_file(source).unwrap().contents();
+        let expanded = expand_partials(&EXAMPLES, source, contents).unwrap();
+        assert_eq!(expanded.as_ref(), contents);
+    }
+
+    #[test]
+    pub fn resolve_wit_deps_closure() {
+        let deps = WIT_PACKAGES
+            .resolve_closure(&[WitPackageName::from_string("wasi:http").unwrap()])
+            .unwrap();
+        assert_eq!(
+            deps,
+            [
+                "cli",
+                "clocks",
+                "filesystem",
+                "http",
+                "io",
+                "random",
+                "sockets"
+            ]
+            .into_iter()
+            .map(PathBuf::from)
+            .collect()
+        );
+    }
+
+    #[test]
+    pub fn resolve_unknown_wit_dep() {
+        assert!(WIT_PACKAGES
+            .resolve_closure(&[WitPackageName::from_string("wasi:unknown").unwrap()])
+            .is_err());
+    }
+
+    #[test]
+    pub fn embedded_adapters() {
+        let adapters = all_adapters();
+        for tier in [GuestLanguageTier::Tier1, GuestLanguageTier::Tier2] {
+            assert!(
+                adapters
+                    .iter()
+                    .any(|adapter| adapter.tier == tier && adapter.sha256.len() == 64),
+                "adapter is not embedded for {}",
+                tier.name()
+            );
+        }
+    }
+
+    #[test]
+    pub fn declared_template_parameters() {
+        let mut example = example("rust-default");
+        example.parameters = vec![
+            TemplateParameter {
+                name: "greeting".to_string(),
+                description: "Greeting".to_string(),
+                placeholder: "__greeting__".to_string(),
+                default: Some("Hello".to_string()),
+            },
+            TemplateParameter {
+                name: "port".to_string(),
+                description: "Port".to_string(),
+                placeholder: "__port__".to_string(),
+                default: None,
+            },
+        ];
+        let mut parameters = parameters(Path::new("my-component"));
+
+        assert!(check_template_parameters(&example, &parameters).is_err());
+
+        parameters
+            .template_parameters
+            .insert("port".to_string(), "8080".to_string());
+        assert!(check_template_parameters(&example, &parameters).is_ok());
+        assert_eq!(
+            transform(
+                "__greeting__ from component_name on __port__",
+                &example,
+                &parameters
+            ),
+            "Hello from my_component on 8080"
+        );
+
+        parameters
+            .template_parameters
+            .insert("unknown".to_string(), "value".to_string());
+        assert!(check_template_parameters(&example, &parameters).is_err());
+    }
+
+    #[test]
+    pub fn planned_files_of_example() {
+        let example = example("rust-default");
+        let parameters = parameters(Path::new("my-component"));
+        let files = planned_files(&example, &parameters).unwrap();
+        assert!(files.contains(&PathBuf::from("Cargo.toml")));
+        assert!(files.contains(&PathBuf::from("wit/my-component.wit")));
+        assert!(!files.iter().any(|file| file.ends_with("metadata.json")));
+    }
+
+    #[test]
+    pub fn conflicts_and_backup() {
+        let project = TestProject::new("rust-default");
+        let (example, parameters) = (&project.example, &project.parameters);
+        let target_path = project.path();
+
+        assert!(
+            target_conflicts(example, parameters, TargetExistsResolveMode::Fail)
+                .unwrap()
+                .is_empty()
+        );
+
+        std::fs::create_dir_all(target_path).unwrap();
+        std::fs::write(target_path.join("Cargo.toml"), "user").unwrap();
+        assert_eq!(
+            target_conflicts(example, parameters, TargetExistsResolveMode::Fail).unwrap(),
+            vec![TargetConflict {
+                path: PathBuf::from("Cargo.toml"),
+                resolution: ConflictResolution::Fail
+            }]
+        );
+
+        assert!(matches!(
+            instantiate_example(example, parameters, TargetExistsResolveMode::Fail),
+            Err(Error::Conflict { path }) if path == target_path.join("Cargo.toml")
+        ));
+        instantiate_example(example, parameters, TargetExistsResolveMode::Backup).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(target_path.join("Cargo.toml.bak")).unwrap(),
+            "user"
+        );
+        assert!(std::fs::read_to_string(target_path.join("Cargo.toml"))
+            .unwrap()
+            .contains("name = \"my-component\""));
+    }
+
+    #[test]
+    pub fn find_app_component_templates() {
+        let (common, component) =
+            find_composable_app_example(GuestLanguage::Rust, None, None).unwrap();
+        assert!(common.is_some());
+        assert_eq!(
+            component.kind,
+            ExampleKind::ComposableAppComponent {
+                group: ComposableAppGroupName::default()
+            }
+        );
+
+        let (_, by_name) =
+            find_composable_app_example(GuestLanguage::Rust, None, Some(&component.name)).unwrap();
+        assert_eq!(by_name.name, component.name);
+
+        let err = find_composable_app_example(
+            GuestLanguage::Rust,
+            Some(&ComposableAppGroupName::from_string("unknown")),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("available groups: default"));
+
+        let err = find_composable_app_example(
+            GuestLanguage::Rust,
+            None,
+            Some(&ExampleName::from_string("unknown")),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains(component.name.as_string()));
+    }
+
+    #[test]
+    pub fn instantiate_app_from_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        let target_path = dir.path().join("app");
+        let component = |name: &str, template: Option<&str>| AppComponentSpec {
+            name: name.to_string(),
+            component_name: None,
+            language: "rust".to_string(),
+            group: None,
+            template: template.map(ExampleName::from_string),
+            parameters: BTreeMap::new(),
+        };
+
+        let invalid = AppSpec {
+            components: vec![
+                component("app:first", None),
+                component("app:second", Some("unknown")),
+            ],
+        };
+        assert!(matches!(
+            instantiate_app(&invalid, &target_path),
+            Err(Error::Catalog { .. })
+        ));
+        assert!(!target_path.exists());
+
+        let duplicated = AppSpec {
+            components: vec![component("app:first", None), component("app:first", None)],
+        };
+        assert!(matches!(
+            instantiate_app(&duplicated, &target_path),
+            Err(Error::Validation { .. })
+        ));
+
+        let spec = AppSpec {
+            components: vec![component("app:first", None), component("app:second", None)],
+        };
+        instantiate_app(&spec, &target_path).unwrap();
+        assert!(target_path.join("components-rust/app-first").is_dir());
+        assert!(target_path.join("components-rust/app-second").is_dir());
+        assert!(matches!(
+            instantiate_app(&spec, &target_path),
+            Err(Error::Conflict { path }) if path == std::path::absolute(&target_path).unwrap()
+        ));
+    }
+
+    #[test]
+    pub fn render_single_file() {
+        let example = example("rust-default");
+        let parameters = parameters(Path::new("my-component"));
+
+        let (target, contents) =
+            render_example_file(&example, &parameters, Path::new("wit/component-name.wit"))
+                .unwrap();
+        assert_eq!(target, PathBuf::from("wit/my-component.wit"));
+        let contents = String::from_utf8(contents).unwrap();
+        assert!(contents.contains("package my:pack;"));
+        assert!(contents.contains("world my-component"));
+
+        let (target, _) =
+            render_example_file(&example, &parameters, Path::new("Cargo.toml")).unwrap();
+        assert_eq!(target, PathBuf::from("Cargo.toml"));
+
+        assert!(matches!(
+            render_example_file(&example, &parameters, Path::new("unknown")),
+            Err(Error::Catalog { .. })
+        ));
+    }
+
+    #[test]
+    pub fn find_example_by_name_reports_unknown_examples() {
+        let example = find_example_by_name(&ExampleName::from_string("rust-default")).unwrap();
+        assert_eq!(example.language, GuestLanguage::Rust);
+        assert!(matches!(
+            find_example_by_name(&ExampleName::from_string("rust-nonexistent")),
+            Err(Error::Catalog { path: None, message }) if message.contains("list-examples")
+        ));
     }
 }
@@ -0,0 +1,495 @@
+use crate::model::{
+    ComponentName, Example, ExampleKind, ExampleName, ExampleParameters, GuestLanguage, PackageName,
+};
+use crate::{
+    all_composable_app_examples, all_examples, declared_wit_deps, inferred_wit_deps,
+    render_template_file, template_files, TemplateFile, COMPONENT_NAME_PLACEHOLDERS,
+    PACKAGE_NAME_PLACEHOLDERS,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+
+static TEMPLATE_EXPRESSION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{.*?}}").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LintSeverity::Warning => write!(f, "warning"),
+            LintSeverity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lint {
+    pub severity: LintSeverity,
+    pub language: GuestLanguage,
+    pub example: ExampleName,
+    /// Template file the lint is about, relative to the example directory
+    pub file: Option<PathBuf>,
+    pub message: String,
+}
+
+impl Lint {
+    fn new(severity: LintSeverity, example: &Example, message: impl Into<String>) -> Lint {
+        Lint {
+            severity,
+            language: example.language,
+            example: example.name.clone(),
+            file: None,
+            message: message.into(),
+        }
+    }
+
+    fn with_file(self, file: impl Into<PathBuf>) -> Lint {
+        Lint {
+            file: Some(file.into()),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(
+                f,
+                "{}: {} ({}): {}: {}",
+                self.severity,
+                self.example,
+                self.language,
+                file.display(),
+                self.message
+            ),
+            None => write!(
+                f,
+                "{}: {} ({}): {}",
+                self.severity, self.example, self.language, self.message
+            ),
+        }
+    }
+}
+
+/// Checks every example in the catalog
+pub fn lint_catalog() -> Vec<Lint> {
+    let mut lints = vec![];
+    for example in all_examples() {
+        let files = rendered_files(&example).unwrap_or_else(|err| {
+            lints.push(Lint::new(LintSeverity::Error, &example, err.to_string()));
+            vec![]
+        });
+        lints.extend(lint_wit_deps(
+            &example,
+            declared_wit_deps(&example),
+            inferred_wit_deps(&example),
+        ));
+        lints.extend(lint_exclude(&example));
+        lints.extend(lint_transform_exclude(&example, &files));
+        lints.extend(lint_untransformed_placeholders(&example, &files));
+        lints.extend(lint_cargo_toml(&example, &files));
+    }
+    for groups in all_composable_app_examples().values() {
+        for app_example in groups.values() {
+            if let Some(common) = &app_example.common {
+                lints.extend(lint_app_common_skip_if_exists(
+                    common,
+                    // Errors of listing the files are reported with the other checks of the example
+                    &rendered_files(common).unwrap_or_default(),
+                ));
+            }
+            for component in &app_example.components {
+                lints.extend(lint_app_component_wit_deps(
+                    app_example.common.as_ref(),
+                    component,
+                ));
+            }
+        }
+    }
+    lints.sort();
+    lints
+}
+
+/// The declared dependencies are used when set, so they should match the inferred ones
+fn lint_wit_deps(
+    example: &Example,
+    declared: Option<BTreeSet<PathBuf>>,
+    inferred: Option<BTreeSet<PathBuf>>,
+) -> Vec<Lint> {
+    match (declared, inferred) {
+        (Some(declared), Some(inferred)) if declared != inferred => vec![Lint::new(
+            LintSeverity::Warning,
+            example,
+            format!(
+                "Declared WIT dependencies differ from the ones inferred from the WIT files, not declared: [{}], not used: [{}]",
+                format_paths(inferred.difference(&declared)),
+                format_paths(declared.difference(&inferred)),
+            ),
+        )],
+        _ => vec![],
+    }
+}
+
+/// Exclude entries with surrounding whitespace never match, entries not matching any file of the
+/// template are not reported, as they are usually build outputs
+fn lint_exclude(example: &Example) -> Vec<Lint> {
+    let mut exclude = example.exclude.iter().collect::<Vec<_>>();
+    exclude.sort();
+    exclude
+        .into_iter()
+        .filter(|entry| entry.trim() != entry.as_str())
+        .map(|entry| {
+            Lint::new(
+                LintSeverity::Warning,
+                example,
+                format!("Exclude entry \"{entry}\" never matches, because of the surrounding whitespace"),
+            )
+        })
+        .collect()
+}
+
+/// Transform exclude entries are matched against the generated file names
+fn lint_transform_exclude(example: &Example, files: &[RenderedFile]) -> Vec<Lint> {
+    let names = files
+        .iter()
+        .filter_map(|(file, _)| file.target.file_name())
+        .filter_map(|name| name.to_str())
+        .collect::<HashSet<_>>();
+
+    let mut transform_exclude = example.transform_exclude.iter().collect::<Vec<_>>();
+    transform_exclude.sort();
+    transform_exclude
+        .into_iter()
+        .filter(|entry| !names.contains(entry.as_str()))
+        .map(|entry| {
+            Lint::new(
+                LintSeverity::Warning,
+                example,
+                format!(
+                    "Transform exclude entry \"{entry}\" does not match any generated file name"
+                ),
+            )
+        })
+        .collect()
+}
+
+fn lint_untransformed_placeholders(example: &Example, files: &[RenderedFile]) -> Vec<Lint> {
+    files
+        .iter()
+        .filter(|(file, _)| !file.transform)
+        .filter_map(|(file, contents)| {
+            let contents = contents.as_ref().ok()?;
+            // Expressions of the golem-cli templates (e.g. `{{ component_name }}`) are expected in untransformed files
+            let contents = TEMPLATE_EXPRESSION_REGEX.replace_all(contents, "");
+            let placeholders = COMPONENT_NAME_PLACEHOLDERS
+                .iter()
+                .chain(PACKAGE_NAME_PLACEHOLDERS)
+                .filter(|placeholder| contents.contains(*placeholder))
+                .copied()
+                .collect::<Vec<_>>();
+            (!placeholders.is_empty()).then(|| {
+                Lint::new(
+                    LintSeverity::Warning,
+                    example,
+                    format!(
+                        "Placeholders are not replaced, because the file is not transformed: {}",
+                        placeholders.join(", ")
+                    ),
+                )
+                .with_file(&file.source)
+            })
+        })
+        .collect()
+}
+
+fn lint_cargo_toml(example: &Example, files: &[RenderedFile]) -> Vec<Lint> {
+    files
+        .iter()
+        .filter(|(file, _)| {
+            file.source
+                .file_name()
+                .is_some_and(|name| name == "Cargo.toml._")
+        })
+        .filter_map(|(file, contents)| {
+            let result = contents
+                .as_ref()
+                .map_err(|err| err.clone())
+                .and_then(|contents| {
+                    toml::from_str::<toml::Table>(contents).map_err(|err| err.to_string())
+                });
+            result.err().map(|err| {
+                Lint::new(
+                    LintSeverity::Error,
+                    example,
+                    format!("Generated Cargo.toml is not valid TOML: {err}"),
+                )
+                .with_file(&file.source)
+            })
+        })
+        .collect()
+}
+
+fn lint_app_common_skip_if_exists(common: &Example, files: &[RenderedFile]) -> Vec<Lint> {
+    let ExampleKind::ComposableAppCommon {
+        skip_if_exists: Some(skip_if_exists),
+        ..
+    } = &common.kind
+    else {
+        return vec![];
+    };
+
+    let produced = files.iter().any(|(file, _)| &file.target == skip_if_exists);
+    if produced {
+        vec![]
+    } else {
+        vec![Lint::new(
+            LintSeverity::Error,
+            common,
+            format!(
+                "appCommonSkipIfExists points to {}, which is not produced by the template, so the template is applied every time",
+                skip_if_exists.display()
+            ),
+        )]
+    }
+}
+
+fn lint_app_component_wit_deps(common: Option<&Example>, component: &Example) -> Vec<Lint> {
+    let Some(inferred) = inferred_wit_deps(component) else {
+        return vec![];
+    };
+
+    let provided: BTreeSet<PathBuf> = common
+        .filter(|common| matches!(common.kind, ExampleKind::ComposableAppCommon { .. }))
+        .map(|common| common.wit_deps.iter().cloned().collect())
+        .unwrap_or_default();
+
+    let missing: BTreeSet<&PathBuf> = inferred.difference(&provided).collect();
+    if missing.is_empty() {
+        vec![]
+    } else {
+        vec![Lint::new(
+            LintSeverity::Warning,
+            component,
+            format!(
+                "WIT dependencies inferred from the WIT files are not provided by the common template: [{}]",
+                format_paths(missing.into_iter()),
+            ),
+        )]
+    }
+}
+
+fn lint_parameters() -> ExampleParameters {
+    ExampleParameters {
+        component_name: ComponentName::new("lint-component"),
+        package_name: PackageName::from_string("lint:component").unwrap(),
+        target_path: PathBuf::new(),
+        template_parameters: BTreeMap::new(),
+    }
+}
+
+/// A template file with its generated contents, or the error of rendering or decoding them
+type RenderedFile = (TemplateFile, Result<String, String>);
+
+/// Renders the template files of the example with the lint parameters
+fn rendered_files(example: &Example) -> crate::error::Result<Vec<RenderedFile>> {
+    Ok(template_files(example, &lint_parameters())?
+        .into_iter()
+        .map(|file| {
+            let contents = render_template_file(example, &file, &lint_parameters())
+                .map_err(|err| err.to_string())
+                .and_then(|contents| {
+                    String::from_utf8(contents.into_owned()).map_err(|err| err.to_string())
+                });
+            (file, contents)
+        })
+        .collect())
+}
+
+fn format_paths<'a>(paths: impl Iterator<Item = &'a PathBuf>) -> String {
+    paths
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lint::{
+        lint_app_common_skip_if_exists, lint_cargo_toml, lint_catalog, lint_exclude,
+        lint_transform_exclude, lint_untransformed_placeholders, lint_wit_deps, LintSeverity,
+        RenderedFile,
+    };
+    use crate::model::{ComposableAppGroupName, Example, ExampleKind, ExampleName, GuestLanguage};
+    use crate::TemplateFile;
+    use std::collections::{BTreeSet, HashSet};
+    use std::path::PathBuf;
+
+    fn fixture_example(kind: ExampleKind) -> Example {
+        Example {
+            name: ExampleName::from_string("lint-fixture"),
+            kind,
+            language: GuestLanguage::Rust,
+            description: "Lint fixture".to_string(),
+            tags: vec![],
+            example_path: PathBuf::from("rust/lint-fixture"),
+            instructions: String::new(),
+            adapter_source: None,
+            adapter_target: None,
+            wit_deps: vec![],
+            wit_deps_targets: None,
+            exclude: HashSet::new(),
+            transform_exclude: HashSet::new(),
+            transform: true,
+            parameters: vec![],
+        }
+    }
+
+    fn fixture_file(source: &str, transform: bool, contents: &str) -> RenderedFile {
+        (
+            TemplateFile {
+                source: PathBuf::from(source),
+                target: PathBuf::from(source.replace("Cargo.toml._", "Cargo.toml")),
+                contents_source: PathBuf::from("rust/lint-fixture").join(source),
+                transform,
+            },
+            Ok(contents.to_string()),
+        )
+    }
+
+    fn messages(lints: Vec<crate::lint::Lint>) -> Vec<(LintSeverity, String)> {
+        lints
+            .into_iter()
+            .map(|lint| (lint.severity, lint.message))
+            .collect()
+    }
+
+    #[test]
+    pub fn catalog_has_no_lint_errors() {
+        let errors = lint_catalog()
+            .into_iter()
+            .filter(|lint| lint.severity == LintSeverity::Error)
+            .map(|lint| lint.to_string())
+            .collect::<Vec<_>>();
+        assert!(errors.is_empty(), "{}", errors.join("\n"));
+    }
+
+    #[test]
+    pub fn untransformed_placeholders() {
+        let example = fixture_example(ExampleKind::Standalone);
+        let files = vec![
+            fixture_file("src/lib.rs", true, "mod component_name;"),
+            fixture_file("README.md", false, "# component-name for pack:name"),
+            fixture_file("golem.yaml", false, "name: {{ component_name }}"),
+        ];
+
+        let lints = lint_untransformed_placeholders(&example, &files);
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].file, Some(PathBuf::from("README.md")));
+        assert_eq!(lints[0].severity, LintSeverity::Warning);
+        assert!(lints[0].message.ends_with("component-name, pack:name"));
+    }
+
+    #[test]
+    pub fn invalid_cargo_toml() {
+        let example = fixture_example(ExampleKind::Standalone);
+        let files = vec![
+            fixture_file("Cargo.toml._", true, "[package]\nname = \"a\""),
+            fixture_file("nested/Cargo.toml._", true, "[package\nname = \"b\""),
+            fixture_file("config.toml", true, "[invalid"),
+        ];
+
+        let lints = lint_cargo_toml(&example, &files);
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].file, Some(PathBuf::from("nested/Cargo.toml._")));
+        assert_eq!(lints[0].severity, LintSeverity::Error);
+    }
+
+    #[test]
+    pub fn dead_exclude_entries() {
+        let mut example = fixture_example(ExampleKind::Standalone);
+        example.exclude = ["target", "src/binding", " node_modules"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        assert_eq!(
+            messages(lint_exclude(&example)),
+            vec![(
+                LintSeverity::Warning,
+                "Exclude entry \" node_modules\" never matches, because of the surrounding whitespace".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    pub fn dead_transform_exclude_entries() {
+        let mut example = fixture_example(ExampleKind::Standalone);
+        example.transform_exclude = ["Cargo.toml", "Cargo.toml._", "main.rs"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let files = vec![
+            fixture_file("Cargo.toml._", true, ""),
+            fixture_file("src/lib.rs", true, ""),
+        ];
+
+        assert_eq!(
+            messages(lint_transform_exclude(&example, &files)),
+            vec![
+                (
+                    LintSeverity::Warning,
+                    "Transform exclude entry \"Cargo.toml._\" does not match any generated file name".to_string()
+                ),
+                (
+                    LintSeverity::Warning,
+                    "Transform exclude entry \"main.rs\" does not match any generated file name".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn app_common_skip_if_exists() {
+        let common = fixture_example(ExampleKind::ComposableAppCommon {
+            group: ComposableAppGroupName::default(),
+            skip_if_exists: Some(PathBuf::from("common-rust/golem.yaml")),
+        });
+
+        let produced = vec![fixture_file("common-rust/golem.yaml", true, "")];
+        assert!(lint_app_common_skip_if_exists(&common, &produced).is_empty());
+
+        let not_produced = vec![fixture_file("golem.yaml", true, "")];
+        let lints = lint_app_common_skip_if_exists(&common, &not_produced);
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].severity, LintSeverity::Error);
+    }
+
+    #[test]
+    pub fn mismatched_wit_deps() {
+        let example = fixture_example(ExampleKind::Standalone);
+        let deps = |paths: &[&str]| Some(paths.iter().map(PathBuf::from).collect::<BTreeSet<_>>());
+
+        assert!(lint_wit_deps(&example, deps(&["io", "http"]), deps(&["http", "io"])).is_empty());
+        assert!(lint_wit_deps(&example, None, deps(&["io"])).is_empty());
+        assert!(lint_wit_deps(&example, deps(&["io"]), None).is_empty());
+        assert_eq!(
+            messages(lint_wit_deps(&example, deps(&["cli", "io"]), deps(&["http", "io"]))),
+            vec![(
+                LintSeverity::Warning,
+                "Declared WIT dependencies differ from the ones inferred from the WIT files, not declared: [http], not used: [cli]".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::model::{ComponentName, Example, ExampleParameters, PackageName};
+use crate::{
+    adapter_target, all_examples, get_adapter_contents, render_file, render_template_file, sha256,
+    template_files, wit_deps_targets, EXAMPLES, WIT,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the lock file written into the root of generated projects
+pub const TEMPLATE_LOCK_FILE_NAME: &str = ".golem-template.json";
+
+/// Version of the lock file representation, has to be increased on every incompatible change
+pub const TEMPLATE_LOCK_SCHEMA_VERSION: u32 = 1;
+
+/// Records which templates generated a project, with which parameters, and what they generated
+///
+/// Composable apps instantiate multiple templates into the same root, so the lock has an entry
+/// for every instantiated template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateLock {
+    pub schema_version: u32,
+    pub templates: Vec<TemplateLockEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateLockEntry {
+    pub example: String,
+    pub language: String,
+    /// Version of golem-examples which generated the files
+    pub generator_version: String,
+    /// Version of golem-wit providing the WIT dependencies and the adapters
+    pub golem_wit_version: String,
+    /// SHA-256 of the template sources, changes with every change of the template
+    pub template_hash: String,
+    pub component_name: String,
+    pub package_name: String,
+    /// Values of the parameters declared by the template
+    #[serde(default)]
+    pub parameters: BTreeMap<String, String>,
+    /// SHA-256 of the contents written into the project, by path relative to the project root,
+    /// existing files which were kept are left out
+    pub files: BTreeMap<PathBuf, String>,
+    /// Generated contents of the recorded text template files, the base of the three-way merges
+    /// when upgrading the project, stored as a gzip-compressed and base64-encoded JSON object
+    #[serde(with = "compressed_base")]
+    #[schemars(with = "String")]
+    pub base: BTreeMap<PathBuf, String>,
+}
+
+impl TemplateLockEntry {
+    /// Finds the example of the entry in the catalog
+    pub fn find_example(&self) -> Result<Example> {
+        all_examples()
+            .into_iter()
+            .find(|example| example.name.as_string() == self.example)
+            .ok_or_else(|| {
+                Error::catalog(format!(
+                    "Example {} of the template lock is not part of the catalog",
+                    self.example
+                ))
+            })
+    }
+
+    /// Parameters the entry was generated with, for a project at `target_path`
+    pub fn example_parameters(&self, target_path: &Path) -> Result<ExampleParameters> {
+        Ok(ExampleParameters {
+            component_name: ComponentName::new(&self.component_name),
+            package_name: PackageName::from_string(&self.package_name).ok_or_else(|| {
+                Error::validation(format!(
+                    "Invalid package name in the template lock: {}",
+                    self.package_name
+                ))
+            })?,
+            target_path: target_path.to_path_buf(),
+            template_parameters: self.parameters.clone(),
+        })
+    }
+
+    fn is_same_template(&self, other: &TemplateLockEntry) -> bool {
+        self.example == other.example && self.component_name == other.component_name
+    }
+}
+
+/// Creates the lock entry of the example, `files` are the hashes of the contents written into the
+/// project, files which were not written are left out
+pub fn template_lock_entry(
+    example: &Example,
+    parameters: &ExampleParameters,
+    files: BTreeMap<PathBuf, String>,
+) -> Result<TemplateLockEntry> {
+    Ok(TemplateLockEntry {
+        example: example.name.to_string(),
+        language: example.language.id(),
+        generator_version: env!("CARGO_PKG_VERSION").to_string(),
+        golem_wit_version: env!("GOLEM_WIT_VERSION").to_string(),
+        template_hash: template_hash(example, parameters)?,
+        component_name: parameters.component_name.to_string(),
+        package_name: parameters.package_name.to_string_with_colon(),
+        parameters: parameters.template_parameters.clone(),
+        base: template_contents(example, parameters)?
+            .into_iter()
+            .filter(|(path, _)| files.contains_key(path))
+            .filter_map(|(path, contents)| Some((path, String::from_utf8(contents).ok()?)))
+            .collect(),
+        files,
+    })
+}
+
+/// Contents `instantiate_example` generates, by path relative to the target path
+pub(crate) fn generated_contents(
+    example: &Example,
+    parameters: &ExampleParameters,
+) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut result = template_contents(example, parameters)?;
+    result.extend(dependency_contents(example)?);
+    Ok(result)
+}
+
+/// Rendered contents of the template files, by path relative to the target path
+fn template_contents(
+    example: &Example,
+    parameters: &ExampleParameters,
+) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut result = BTreeMap::new();
+    for file in template_files(example, parameters)? {
+        let contents = render_template_file(example, &file, parameters)?;
+        result.insert(file.target, contents.into_owned());
+    }
+    Ok(result)
+}
+
+/// Contents of the adapter and the WIT dependencies, by path relative to the target path
+fn dependency_contents(example: &Example) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut result = BTreeMap::new();
+    if let (Some(source), Some(target)) = (example.adapter_source.clone(), adapter_target(example))
+    {
+        result.insert(target, get_adapter_contents(&source)?.to_vec());
+    }
+    for wit_dep in &example.wit_deps {
+        let Some(dir) = WIT.get_dir(wit_dep) else {
+            continue;
+        };
+        for target_wit_deps in wit_deps_targets(example) {
+            for file in dir.files() {
+                result.insert(
+                    target_wit_deps
+                        .join(wit_dep.file_name().unwrap())
+                        .join(file.path().file_name().unwrap()),
+                    file.contents().to_vec(),
+                );
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Hash of the template sources before applying the template rules, with the partials expanded
+fn template_hash(example: &Example, parameters: &ExampleParameters) -> Result<String> {
+    let mut files = template_files(example, parameters)?;
+    files.sort_by(|a, b| a.source.cmp(&b.source));
+    let mut sources = example.instructions.as_bytes().to_vec();
+    for file in files {
+        let contents = render_file(&EXAMPLES, &file.contents_source, example, parameters, false)?;
+        sources.extend(file.source.to_string_lossy().as_bytes());
+        sources.push(0);
+        sources.extend(sha256(&contents).as_bytes());
+    }
+    Ok(sha256(&sources))
+}
+
+/// Serde representation of the base contents, they would make up most of the lock file as plain
+/// JSON
+mod compressed_base {
+    use base64::prelude::BASE64_STANDARD;
+    use base64::Engine;
+    use flate2::read::GzDecoder;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::BTreeMap;
+    use std::io::{Read, Write};
+    use std::path::PathBuf;
+
+    pub fn serialize<S: Serializer>(
+        base: &BTreeMap<PathBuf, String>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let json = serde_json::to_vec(base).map_err(S::Error::custom)?;
+        let mut encoder = GzEncoder::new(vec![], Compression::best());
+        encoder.write_all(&json).map_err(S::Error::custom)?;
+        let compressed = encoder.finish().map_err(S::Error::custom)?;
+        serializer.serialize_str(&BASE64_STANDARD.encode(compressed))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<PathBuf, String>, D::Error> {
+        let compressed = BASE64_STANDARD
+            .decode(String::deserialize(deserializer)?)
+            .map_err(D::Error::custom)?;
+        let mut json = vec![];
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut json)
+            .map_err(D::Error::custom)?;
+        serde_json::from_slice(&json).map_err(D::Error::custom)
+    }
+}
+
+/// Reads the lock file of the project, returns `None` if the project has no lock file
+pub fn read_template_lock(project_path: &Path) -> Result<Option<TemplateLock>> {
+    let path = project_path.join(TEMPLATE_LOCK_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read(&path).with_path(&path)?;
+    let invalid =
+        |err| Error::validation(format!("Invalid template lock file: {err}")).with_path(&path);
+    // The version is checked first, as locks of other versions may not deserialize at all
+    let lock: serde_json::Value = serde_json::from_slice(&contents).map_err(invalid)?;
+    let schema_version = lock
+        .get("schemaVersion")
+        .and_then(serde_json::Value::as_u64);
+    if schema_version != Some(TEMPLATE_LOCK_SCHEMA_VERSION as u64) {
+        return Err(Error::validation(format!(
+            "Unsupported template lock schema version {}, this version of the generator supports version {TEMPLATE_LOCK_SCHEMA_VERSION}",
+            schema_version.map_or("(missing)".to_string(), |version| version.to_string())
+        ))
+        .with_path(&path));
+    }
+    serde_json::from_value(lock).map(Some).map_err(invalid)
+}
+
+/// Adds the entry to the lock file of the project, replacing the entry of the same example and
+/// component
+pub fn update_template_lock(project_path: &Path, entry: TemplateLockEntry) -> Result<()> {
+    let mut lock = read_template_lock(project_path)?.unwrap_or(TemplateLock {
+        schema_version: TEMPLATE_LOCK_SCHEMA_VERSION,
+        templates: vec![],
+    });
+    match lock
+        .templates
+        .iter_mut()
+        .find(|existing| existing.is_same_template(&entry))
+    {
+        Some(existing) => *existing = entry,
+        None => lock.templates.push(entry),
+    }
+    write_template_lock(project_path, &lock)
+}
+
+/// Writes the lock file of the project, replacing the existing one
+pub(crate) fn write_template_lock(project_path: &Path, lock: &TemplateLock) -> Result<()> {
+    let path = project_path.join(TEMPLATE_LOCK_FILE_NAME);
+    let contents = serde_json::to_string_pretty(lock).map_err(|err| Error::Io {
+        path: path.clone(),
+        source: err.into(),
+    })?;
+    fs::write(&path, contents + "\n").with_path(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::lock::{
+        read_template_lock, TemplateLock, TEMPLATE_LOCK_FILE_NAME, TEMPLATE_LOCK_SCHEMA_VERSION,
+    };
+    use crate::model::TargetExistsResolveMode;
+    use crate::test_fixtures::TestProject;
+    use crate::{instantiate_example, planned_files, sha256};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    pub fn instantiate_writes_lock() {
+        let project = TestProject::new("go-default");
+        let (example, parameters) = (&project.example, &project.parameters);
+        let target_path = project.path();
+        assert!(planned_files(example, parameters)
+            .unwrap()
+            .contains(&PathBuf::from(TEMPLATE_LOCK_FILE_NAME)));
+
+        project.instantiate();
+        instantiate_example(example, parameters, TargetExistsResolveMode::Skip).unwrap();
+
+        let lock = read_template_lock(target_path).unwrap().unwrap();
+        assert_eq!(lock.templates.len(), 1);
+        let entry = &lock.templates[0];
+        assert_eq!(entry.example, "go-default");
+        assert_eq!(entry.package_name, "my:pack");
+        assert_eq!(entry.find_example().unwrap().name, example.name);
+        for path in [
+            Path::new("go.mod"),
+            Path::new("wit/my-component.wit"),
+            Path::new("adapters/tier1/wasi_snapshot_preview1.wasm"),
+            Path::new("wit/deps/io/streams.wit"),
+        ] {
+            assert_eq!(
+                entry.files.get(path),
+                Some(&sha256(&std::fs::read(target_path.join(path)).unwrap())),
+                "{}",
+                path.display()
+            );
+        }
+        assert!(!entry.files.contains_key(Path::new(TEMPLATE_LOCK_FILE_NAME)));
+        assert!(entry.base.contains_key(Path::new("go.mod")));
+
+        // The base contents are stored compressed
+        let json: serde_json::Value = serde_json::from_slice(
+            &std::fs::read(target_path.join(TEMPLATE_LOCK_FILE_NAME)).unwrap(),
+        )
+        .unwrap();
+        assert!(json["templates"][0]["base"].is_string());
+    }
+
+    #[test]
+    pub fn lock_records_written_contents() {
+        let project = TestProject::new("python-default");
+        let target_path = project.path();
+        std::fs::create_dir_all(target_path).unwrap();
+        std::fs::write(target_path.join("main.py"), "user\n").unwrap();
+        std::fs::write(target_path.join(".gitignore"), "user\n").unwrap();
+
+        instantiate_example(
+            &project.example,
+            &project.parameters,
+            TargetExistsResolveMode::MergeOrSkip,
+        )
+        .unwrap();
+
+        let lock = read_template_lock(target_path).unwrap().unwrap();
+        let files = &lock.templates[0].files;
+        assert!(!files.contains_key(Path::new("main.py")));
+        let gitignore = std::fs::read(target_path.join(".gitignore")).unwrap();
+        assert!(String::from_utf8_lossy(&gitignore)
+            .lines()
+            .any(|line| line == "user"));
+        // Merged files record the generated contents, which are the base of later upgrades
+        let base = &lock.templates[0].base[Path::new(".gitignore")];
+        assert_ne!(base.as_bytes(), gitignore.as_slice());
+        assert_eq!(
+            files.get(Path::new(".gitignore")),
+            Some(&sha256(base.as_bytes()))
+        );
+        for (path, hash) in files
+            .iter()
+            .filter(|(path, _)| *path != Path::new(".gitignore"))
+        {
+            assert_eq!(
+                &sha256(&std::fs::read(target_path.join(path)).unwrap()),
+                hash,
+                "{}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    pub fn lock_schema_is_up_to_date() {
+        let schema = serde_json::to_string_pretty(&schemars::schema_for!(TemplateLock)).unwrap();
+        assert_eq!(
+            schema.trim(),
+            include_str!("../schemas/lock.schema.json").trim(),
+            "The lock JSON schema is outdated, update it with: cargo run -- schema lock > schemas/lock.schema.json"
+        );
+    }
+
+    #[test]
+    pub fn reject_unsupported_schema_version() {
+        let project = TestProject::generated("go-default");
+        let path = project.path().join(TEMPLATE_LOCK_FILE_NAME);
+        let mut lock: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        lock["schemaVersion"] = serde_json::json!(TEMPLATE_LOCK_SCHEMA_VERSION + 1);
+        std::fs::write(&path, serde_json::to_string(&lock).unwrap()).unwrap();
+
+        assert!(matches!(
+            read_template_lock(project.path()),
+            Err(Error::Validation { path: Some(error_path), message })
+                if error_path == path && message.contains("schema version")
+        ));
+    }
+}
@@ -1,49 +1,149 @@
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use colored::Colorize;
+use golem_examples::catalog::{Catalog, CatalogExample};
 use golem_examples::cli::*;
+use golem_examples::describe::describe_example;
+use golem_examples::diff::{diff_example, diff_project, FileDiff};
+use golem_examples::doctor::{check_tools, example_tools, language_tools, Tool};
+use golem_examples::error::{Error, FAILED_CHECK_EXIT_CODE};
+use golem_examples::lint::{lint_catalog, LintSeverity};
+use golem_examples::lock::TemplateLock;
 use golem_examples::model::*;
+use golem_examples::rename::{apply_rename, plan_rename};
+use golem_examples::restore::{restore_example, restore_project, RestoreStatus};
+use golem_examples::sync::{sync_example_wit, sync_project_wit, WitSyncStatus};
+use golem_examples::upgrade::{upgrade_project, UpgradeStatus};
+use golem_examples::wizard::{run_wizard, Prompt, TerminalPrompt};
 use golem_examples::{
-    add_component_by_example, all_composable_app_examples, all_standalone_examples,
-    instantiate_example,
+    add_component_by_example, all_adapters, all_composable_app_examples, all_standalone_examples,
+    check_adapters, find_composable_app_example, find_example_by_name, instantiate_app,
+    instantiate_example, metadata_json_schema, refresh_adapters, render_example_file,
+    target_conflicts,
 };
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+
+/// Environment variable used by the registration scripts of the shell completions
+const COMPLETE_VAR: &str = "COMPLETE";
 
 pub fn main() {
+    CompleteEnv::with_factory(GolemCommand::command)
+        .var(COMPLETE_VAR)
+        .complete();
+
     let command: GolemCommand = GolemCommand::parse();
     match &command.command {
         Command::New {
             name_or_language,
             component_name,
             package_name,
+            target,
+            in_place,
+            on_conflict,
+            template_parameters,
         } => {
-            let example_name = name_or_language.example_name();
+            let cwd = current_dir();
+            let target_path = |component_name: &ComponentName| match target {
+                Some(target) => cwd.join(target),
+                None if *in_place => cwd.clone(),
+                None => cwd.join(component_name.as_str()),
+            };
             let examples = all_standalone_examples();
-            let example = examples.iter().find(|example| example.name == example_name);
-            match example {
-                Some(example) => {
-                    let cwd =
-                        std::env::current_dir().expect("Failed to get current working directory");
-                    match instantiate_example(
+            let (example, parameters) = match component_name {
+                Some(component_name) => {
+                    let example_name = name_or_language.example_name();
+                    let example = find_example_by_name(&example_name)
+                        .and_then(|example| match example.kind {
+                            ExampleKind::Standalone => Ok(example),
+                            _ => Err(Error::validation(format!(
+                                "{example_name} is a composable app template, use new-app-component"
+                            ))),
+                        })
+                        .unwrap_or_else(|err| exit_with_error("Failed to select example", err));
+                    (
                         example,
-                        &ExampleParameters {
+                        ExampleParameters {
                             component_name: component_name.clone(),
                             package_name: package_name
                                 .clone()
                                 .unwrap_or(PackageName::from_string("golem:component").unwrap()),
-                            target_path: cwd.join(component_name.as_str()),
+                            target_path: target_path(component_name),
+                            template_parameters: template_parameters.iter().cloned().collect(),
                         },
-                        TargetExistsResolveMode::Fail,
-                    ) {
-                        Ok(instructions) => println!("{instructions}"),
-                        Err(err) => eprintln!("Failed to instantiate example: {err:?}"),
+                    )
+                }
+                None if std::io::stdin().is_terminal()
+                    && name_or_language.example.is_none()
+                    && name_or_language.language.is_none() =>
+                {
+                    match run_wizard(&mut TerminalPrompt::stdio(), &examples, &target_path) {
+                        Ok(Some((example, parameters))) => (example, parameters),
+                        Ok(None) => {
+                            eprintln!("Cancelled");
+                            return;
+                        }
+                        Err(source) => exit_with_error(
+                            "Failed to run the interactive mode",
+                            Error::Io {
+                                path: PathBuf::from("<stdin>"),
+                                source,
+                            },
+                        ),
                     }
                 }
-                None => {
-                    eprintln!("Unknown example {example_name}. Use the list-examples command to see the available commands.");
+                None => exit_with_error(
+                    "Failed to select example",
+                    Error::validation(
+                        "The component name is required when not running interactively on a terminal",
+                    ),
+                ),
+            };
+
+            let resolve_mode = on_conflict.resolve_mode();
+            let conflicts = target_conflicts(&example, &parameters, resolve_mode)
+                .unwrap_or_else(|err| exit_with_error("Failed to check the target directory", err));
+            if !conflicts.is_empty() {
+                eprintln!(
+                    "The following files already exist in {}:",
+                    parameters.target_path.display()
+                );
+                for conflict in &conflicts {
+                    eprintln!(
+                        "  {:<8} {}",
+                        conflict.resolution.to_string(),
+                        conflict.path.display()
+                    );
                 }
+                if let Some(conflict) = conflicts
+                    .iter()
+                    .find(|conflict| conflict.resolution == ConflictResolution::Fail)
+                {
+                    eprintln!("Nothing was written, use --on-conflict to skip, merge or back up the existing files");
+                    let err = Error::Conflict {
+                        path: parameters.target_path.join(&conflict.path),
+                    };
+                    std::process::exit(err.exit_code());
+                }
+            }
+
+            match instantiate_example(&example, &parameters, resolve_mode) {
+                Ok(instructions) => println!("{instructions}"),
+                Err(err) => exit_with_error("Failed to instantiate example", err),
             }
         }
-        Command::ListExamples { min_tier, language } => {
-            all_standalone_examples()
-                .iter()
+        Command::ListExamples {
+            min_tier,
+            language,
+            format,
+        } => {
+            let examples = all_standalone_examples()
+                .into_iter()
                 .filter(|example| match language {
                     Some(language) => example.language == *language,
                     None => true,
@@ -52,67 +152,735 @@ pub fn main() {
                     Some(min_tier) => example.language.tier() <= *min_tier,
                     None => true,
                 })
-                .for_each(|example| println!("{:?}", example));
+                .collect::<Vec<_>>();
+            let catalog = Catalog::new(&examples);
+            match format {
+                OutputFormat::Table => print_examples_table(&catalog.examples),
+                OutputFormat::Plain => print_examples_plain(&catalog.examples),
+                OutputFormat::Json | OutputFormat::Yaml => print_serialized(&catalog, *format),
+            }
         }
         Command::ListAppExamples {
             language: language_filter,
             group: group_filter,
+            format,
         } => {
-            for (language, examples) in all_composable_app_examples() {
+            let mut examples = vec![];
+            for (language, groups) in all_composable_app_examples() {
                 if let Some(language_filter) = language_filter {
                     if language_filter != &language {
                         continue;
                     }
                 }
 
-                for (group, examples) in examples {
+                for (group, app_examples) in groups {
                     if let Some(group_filter) = group_filter {
                         if group_filter != &group {
                             continue;
                         }
                     }
 
-                    if let Some(common) = examples.common {
-                        println!("{language} - {group} - common");
-                        println!("{:?}\n", common);
+                    examples.extend(app_examples.common);
+                    examples.extend(app_examples.components);
+                }
+            }
+            let catalog = Catalog::new(&examples);
+            match format {
+                OutputFormat::Table => print_app_examples_tree(&catalog),
+                OutputFormat::Plain => print_examples_plain(&catalog.examples),
+                OutputFormat::Json | OutputFormat::Yaml => print_serialized(&catalog, *format),
+            }
+        }
+        Command::Describe {
+            example: example_name,
+            component_name,
+            package_name,
+            template_parameters,
+            format,
+        } => {
+            let example = find_example_by_name(example_name)
+                .unwrap_or_else(|err| exit_with_error("Failed to describe example", err));
+            let parameters = ExampleParameters {
+                component_name: component_name.clone(),
+                package_name: package_name.clone(),
+                target_path: PathBuf::from(component_name.as_str()),
+                template_parameters: template_parameters.iter().cloned().collect(),
+            };
+            match describe_example(&example, &parameters) {
+                Ok(description) => match format {
+                    OutputFormat::Table | OutputFormat::Plain => println!("{description}"),
+                    OutputFormat::Json | OutputFormat::Yaml => {
+                        print_serialized(&description, *format)
+                    }
+                },
+                Err(err) => exit_with_error("Failed to describe example", err),
+            }
+        }
+        Command::Render {
+            example: example_name,
+            path,
+            component_name,
+            package_name,
+            template_parameters,
+            name,
+        } => {
+            let example = find_example_by_name(example_name)
+                .unwrap_or_else(|err| exit_with_error("Failed to render file", err));
+            let parameters = ExampleParameters {
+                component_name: component_name.clone(),
+                package_name: package_name.clone(),
+                target_path: PathBuf::from(component_name.as_str()),
+                template_parameters: template_parameters.iter().cloned().collect(),
+            };
+            match render_example_file(&example, &parameters, path) {
+                Ok((target, _)) if *name => println!("{}", target.display()),
+                Ok((_, contents)) => {
+                    if let Err(source) = std::io::stdout().write_all(&contents) {
+                        exit_with_error(
+                            "Failed to render file",
+                            Error::Io {
+                                path: PathBuf::from("<stdout>"),
+                                source,
+                            },
+                        )
+                    }
+                }
+                Err(err) => exit_with_error("Failed to render file", err),
+            }
+        }
+        Command::Doctor { language, example } => {
+            let targets: Vec<(String, Vec<&'static Tool>)> = match (language, example) {
+                (_, Some(example_name)) => {
+                    let example = find_example_by_name(example_name)
+                        .unwrap_or_else(|err| exit_with_error("Failed to check tools", err));
+                    vec![(example.name.to_string(), example_tools(&example))]
+                }
+                (Some(language), None) => vec![(language.to_string(), language_tools(*language))],
+                (None, None) => GuestLanguage::iter()
+                    .map(|language| (language.to_string(), language_tools(language)))
+                    .filter(|(_, tools)| !tools.is_empty())
+                    .collect(),
+            };
+
+            let mut has_problems = false;
+            for (title, tools) in targets {
+                println!("{title}");
+                if tools.is_empty() {
+                    println!("  no required tools are declared");
+                }
+                for check in check_tools(&tools, |name| std::env::var_os(name)) {
+                    println!(
+                        "  {:<16} {:<10} {:<40} {}",
+                        check.tool.name,
+                        check.version.as_deref().unwrap_or("-"),
+                        check
+                            .path
+                            .as_ref()
+                            .map(|path| path.display().to_string())
+                            .unwrap_or("-".to_string()),
+                        check.status
+                    );
+                    if let Some(fix) = check.fix() {
+                        has_problems = true;
+                        println!("    fix: {fix}");
+                    }
+                }
+            }
+            if has_problems {
+                std::process::exit(FAILED_CHECK_EXIT_CODE);
+            }
+        }
+        Command::Lint { format } => {
+            let lints = lint_catalog();
+            match format {
+                OutputFormat::Table | OutputFormat::Plain => {
+                    for lint in &lints {
+                        println!("{lint}");
+                    }
+                    if *format == OutputFormat::Table {
+                        let count = |severity: LintSeverity| {
+                            lints
+                                .iter()
+                                .filter(|lint| lint.severity == severity)
+                                .count()
+                        };
+                        println!(
+                            "{} errors, {} warnings",
+                            count(LintSeverity::Error),
+                            count(LintSeverity::Warning)
+                        );
+                    }
+                }
+                OutputFormat::Json | OutputFormat::Yaml => print_serialized(&lints, *format),
+            }
+            if lints
+                .iter()
+                .any(|lint| lint.severity == LintSeverity::Error)
+            {
+                std::process::exit(FAILED_CHECK_EXIT_CODE);
+            }
+        }
+        Command::Schema { kind } => {
+            let schema = match kind {
+                SchemaKind::Catalog => schemars::schema_for!(Catalog),
+                SchemaKind::Metadata => metadata_json_schema(),
+                SchemaKind::Lock => schemars::schema_for!(TemplateLock),
+            };
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&schema).expect("Failed to serialize schema")
+            );
+        }
+        Command::Completions { shell } => {
+            let exe = std::env::current_exe().unwrap_or_else(|source| {
+                exit_with_error(
+                    "Failed to get the current executable",
+                    Error::Io {
+                        path: PathBuf::new(),
+                        source,
+                    },
+                )
+            });
+            let bin = exe
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| GolemCommand::command().get_name().to_string());
+            let result = shell.completer().write_registration(
+                COMPLETE_VAR,
+                &bin,
+                &bin,
+                &exe.to_string_lossy(),
+                &mut std::io::stdout(),
+            );
+            // A closed stdout (eg. piping into `head`) is not an error
+            match result {
+                Err(source) if source.kind() != std::io::ErrorKind::BrokenPipe => exit_with_error(
+                    "Failed to write the completion script",
+                    Error::Io {
+                        path: PathBuf::from("<stdout>"),
+                        source,
+                    },
+                ),
+                _ => {}
+            }
+        }
+        Command::ListAdapters => {
+            for adapter in all_adapters() {
+                println!(
+                    "{} - {} - sha256: {}",
+                    adapter.tier,
+                    adapter.path.display(),
+                    adapter.sha256
+                );
+            }
+        }
+        Command::CheckAdapters { path, refresh } => {
+            let path = path.clone().unwrap_or_else(current_dir);
+            let result = if *refresh {
+                refresh_adapters(&path)
+            } else {
+                check_adapters(&path)
+            };
+            match result {
+                Ok(checks) => {
+                    if checks.is_empty() {
+                        println!("No adapters found in {}", path.display());
+                    }
+                    for check in checks {
+                        match check.status {
+                            AdapterStatus::UpToDate => println!(
+                                "{}: up to date ({})",
+                                check.path.display(),
+                                check.tier
+                            ),
+                            AdapterStatus::Outdated => eprintln!(
+                                "warning: {}: differs from the embedded {} adapter, use --refresh to update it",
+                                check.path.display(),
+                                check.tier
+                            ),
+                            AdapterStatus::Refreshed => println!(
+                                "{}: refreshed ({})",
+                                check.path.display(),
+                                check.tier
+                            ),
+                        }
+                    }
+                }
+                Err(err) => exit_with_error("Failed to check adapters", err),
+            }
+        }
+        Command::SyncWit {
+            path,
+            example,
+            dry_run,
+        } => {
+            let path = path.clone().unwrap_or_else(current_dir);
+            let changes = match example {
+                Some(example_name) => {
+                    let example = find_example_by_name(example_name)
+                        .unwrap_or_else(|err| exit_with_error("Failed to sync WIT", err));
+                    let parameters = ExampleParameters {
+                        component_name: ComponentName::new("my-component"),
+                        package_name: PackageName::from_string("golem:component").unwrap(),
+                        target_path: path.clone(),
+                        template_parameters: BTreeMap::new(),
+                    };
+                    sync_example_wit(&example, &parameters, *dry_run)
+                }
+                None => sync_project_wit(&path, *dry_run),
+            };
+            match changes {
+                Ok(changes) => {
+                    let up_to_date = changes
+                        .iter()
+                        .filter(|change| !change.status.needs_sync())
+                        .count();
+                    for change in changes.iter().filter(|change| change.status.needs_sync()) {
+                        let status = format!("{:<8}", change.status.to_string());
+                        let status = match change.status {
+                            WitSyncStatus::Extra => status.yellow(),
+                            _ => status.green(),
+                        };
+                        match &change.package {
+                            Some(package) => {
+                                println!("  {} {} ({})", status, change.path.display(), package)
+                            }
+                            None => println!("  {} {}", status, change.path.display()),
+                        }
                     }
-                    for example in examples.components.iter() {
-                        println!("{language} - {group} - component");
-                        println!("{:?}\n", example);
+                    if up_to_date == changes.len() {
+                        println!(
+                            "{} is in sync with golem-wit {}",
+                            path.display(),
+                            env!("GOLEM_WIT_VERSION")
+                        );
+                    } else if *dry_run {
+                        println!("{up_to_date} up to date, nothing was written");
+                    } else {
+                        println!("{up_to_date} up to date, the others were updated");
                     }
                 }
+                Err(err) => exit_with_error("Failed to sync WIT", err),
+            }
+        }
+        Command::Rename {
+            path,
+            component,
+            component_name,
+            package_name,
+            dry_run,
+            yes,
+        } => {
+            let path = path.clone().unwrap_or_else(current_dir);
+            let plan = match plan_rename(
+                &path,
+                component.as_deref(),
+                component_name.as_ref(),
+                package_name.as_ref(),
+            ) {
+                Ok(plan) => plan,
+                Err(err) => exit_with_error("Failed to rename", err),
+            };
+            if plan.files.is_empty() {
+                println!("Nothing to rename in {}", path.display());
+                return;
+            }
+            println!("Renaming:");
+            for (old, new) in &plan.names {
+                println!("  {} -> {}", old.red(), new.green());
+            }
+            println!("Files:");
+            for file in plan
+                .files
+                .iter()
+                .filter(|file| file.is_moved() || file.is_edited())
+            {
+                if file.is_moved() {
+                    println!(
+                        "  {} {} -> {}{}",
+                        "move".yellow(),
+                        file.path.display(),
+                        file.new_path.display(),
+                        if file.is_edited() { " (edited)" } else { "" }
+                    );
+                } else {
+                    println!("  {} {}", "edit".green(), file.path.display());
+                }
+            }
+            if *dry_run {
+                return;
+            }
+            if !*yes {
+                if !std::io::stdin().is_terminal() {
+                    exit_with_error(
+                        "Failed to rename",
+                        Error::validation(
+                            "Nothing was written, use --yes to apply the changes when not running interactively",
+                        ),
+                    );
+                }
+                match TerminalPrompt::stdio().confirm("Apply the changes?", false) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        eprintln!("Cancelled");
+                        return;
+                    }
+                    Err(source) => exit_with_error(
+                        "Failed to read the confirmation",
+                        Error::Io {
+                            path: PathBuf::from("<stdin>"),
+                            source,
+                        },
+                    ),
+                }
+            }
+            match apply_rename(&path, &plan) {
+                Ok(()) => println!("Renamed {} files", plan.files.len()),
+                Err(err) => exit_with_error("Failed to rename", err),
+            }
+        }
+        Command::Upgrade { path, dry_run } => {
+            let path = path.clone().unwrap_or_else(current_dir);
+            match upgrade_project(&path, *dry_run) {
+                Ok(changes) => {
+                    if changes.is_empty() {
+                        println!("{} is up to date", path.display());
+                    }
+                    for change in &changes {
+                        let status = format!("{:<8}", change.status.to_string());
+                        let status = match change.status {
+                            UpgradeStatus::Conflict | UpgradeStatus::Rejected => status.red(),
+                            UpgradeStatus::Kept => status.yellow(),
+                            _ => status.green(),
+                        };
+                        println!("  {} {}", status, change.path.display());
+                    }
+                    if changes.iter().any(|change| change.status.is_conflict()) {
+                        eprintln!("Resolve the conflict markers and the .rej files manually");
+                        let err = Error::Merge {
+                            path: path.clone(),
+                            message: "conflicting changes".to_string(),
+                        };
+                        std::process::exit(err.exit_code());
+                    }
+                }
+                Err(err) => exit_with_error("Failed to upgrade project", err),
+            }
+        }
+        Command::Diff {
+            path,
+            example,
+            component_name,
+            package_name,
+            template_parameters,
+            stat,
+        } => {
+            let path = path.clone().unwrap_or_else(current_dir);
+            let diffs = match example {
+                Some(example_name) => {
+                    let example = find_example_by_name(example_name)
+                        .unwrap_or_else(|err| exit_with_error("Failed to diff project", err));
+                    let parameters = ExampleParameters {
+                        component_name: component_name.clone(),
+                        package_name: package_name.clone(),
+                        target_path: path.clone(),
+                        template_parameters: template_parameters.iter().cloned().collect(),
+                    };
+                    diff_example(&example, &parameters)
+                }
+                None => diff_project(&path),
+            };
+            match diffs {
+                Ok(diffs) if *stat => print_diff_stat(&diffs),
+                Ok(diffs) => {
+                    let color = colored::control::SHOULD_COLORIZE.should_colorize();
+                    for diff in &diffs {
+                        print!("{}", diff.unified_diff(color));
+                    }
+                }
+                Err(err) => exit_with_error("Failed to diff project", err),
+            }
+        }
+        Command::Restore {
+            patterns,
+            path,
+            example,
+            component_name,
+            package_name,
+            template_parameters,
+            overwrite,
+            dry_run,
+        } => {
+            let path = path.clone().unwrap_or_else(current_dir);
+            let changes = match example {
+                Some(example_name) => {
+                    let example = find_example_by_name(example_name)
+                        .unwrap_or_else(|err| exit_with_error("Failed to restore files", err));
+                    let parameters = ExampleParameters {
+                        component_name: component_name.clone(),
+                        package_name: package_name.clone(),
+                        target_path: path.clone(),
+                        template_parameters: template_parameters.iter().cloned().collect(),
+                    };
+                    restore_example(&example, &parameters, patterns, *overwrite, *dry_run)
+                }
+                None => restore_project(&path, patterns, *overwrite, *dry_run),
+            };
+            match changes {
+                Ok(changes) => {
+                    if changes.is_empty() {
+                        println!("No files to restore in {}", path.display());
+                    }
+                    for change in &changes {
+                        let status = format!("{:<11}", change.status.to_string());
+                        let status = match change.status {
+                            RestoreStatus::Skipped => status.yellow(),
+                            _ => status.green(),
+                        };
+                        println!("  {} {}", status, change.path.display());
+                    }
+                    if changes
+                        .iter()
+                        .any(|change| change.status == RestoreStatus::Skipped)
+                    {
+                        eprintln!("Skipped files differ from the template output, use --overwrite to replace them");
+                    }
+                }
+                Err(err) => exit_with_error("Failed to restore files", err),
+            }
+        }
+        Command::NewApp { spec, target } => {
+            let spec_path = spec;
+            let spec: AppSpec = match std::fs::read_to_string(spec_path)
+                .map_err(|source| Error::Io {
+                    path: spec_path.clone(),
+                    source,
+                })
+                .and_then(|spec| {
+                    serde_yaml::from_str(&spec)
+                        .map_err(|err| Error::validation(err.to_string()).with_path(spec_path))
+                }) {
+                Ok(spec) => spec,
+                Err(err) => exit_with_error("Failed to read app spec", err),
+            };
+            let target = target.clone().unwrap_or_else(current_dir);
+            match instantiate_app(&spec, &target) {
+                Ok(instructions) => println!("{instructions}"),
+                Err(err) => exit_with_error("Failed to create the application", err),
             }
         }
         Command::NewAppComponent {
+            package_name,
             component_name,
             language,
+            group,
+            template,
+            template_parameters,
         } => {
-            let all_examples = all_composable_app_examples();
+            let (common_example, component_example) =
+                match find_composable_app_example(*language, group.as_ref(), template.as_ref()) {
+                    Ok(examples) => examples,
+                    Err(err) => {
+                        eprintln!(
+                            "{} Failed to select the component template: {err}",
+                            "error:".red().bold()
+                        );
+                        eprintln!(
+                            "Use the list-app-examples command to see the available templates."
+                        );
+                        std::process::exit(err.exit_code());
+                    }
+                };
 
-            let language_examples = all_examples
-                .get(language)
-                .expect("No template found for the selected language");
+            match add_component_by_example(
+                common_example.as_ref(),
+                &component_example,
+                &current_dir(),
+                &component_name
+                    .clone()
+                    .unwrap_or_else(|| ComponentName::from_package_name(package_name)),
+                package_name,
+                &template_parameters.iter().cloned().collect(),
+            ) {
+                Ok(_) => {}
+                Err(err) => exit_with_error("Failed to add the component", err),
+            }
+        }
+    }
+}
 
-            let default_examples = language_examples
-                .get(&ComposableAppGroupName::default())
-                .expect("No default template found for the selected language");
+/// Prints the error and exits with its exit code
+fn exit_with_error(context: impl Display, err: Error) -> ! {
+    eprintln!("{} {context}: {err}", "error:".red().bold());
+    std::process::exit(err.exit_code())
+}
 
-            assert_eq!(
-                default_examples.components.len(),
-                1,
-                "Expected exactly one default component template"
-            );
+fn current_dir() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|source| {
+        exit_with_error(
+            "Failed to get the current working directory",
+            Error::Io {
+                path: Path::new(".").to_path_buf(),
+                source,
+            },
+        )
+    })
+}
 
-            let default_component_example = &default_examples.components[0];
+fn print_diff_stat(diffs: &[FileDiff]) {
+    let width = diffs
+        .iter()
+        .map(|diff| diff.path.display().to_string().len())
+        .max()
+        .unwrap_or(0);
+    let mut total_insertions = 0;
+    let mut total_deletions = 0;
+    for diff in diffs {
+        let path = diff.path.display().to_string();
+        match diff.line_stat() {
+            Some((insertions, deletions)) => {
+                total_insertions += insertions;
+                total_deletions += deletions;
+                println!(
+                    " {:<width$} | {:>5} {}{}",
+                    path,
+                    insertions + deletions,
+                    "+".repeat(insertions.min(40)).green(),
+                    "-".repeat(deletions.min(40)).red()
+                );
+            }
+            None => println!(" {path:<width$} |   Bin"),
+        }
+    }
+    println!(
+        " {} files changed, {} insertions(+), {} deletions(-)",
+        diffs.len(),
+        total_insertions,
+        total_deletions
+    );
+}
 
-            match add_component_by_example(
-                default_examples.common.as_ref(),
-                default_component_example,
-                &std::env::current_dir().expect("Failed to get current working directory"),
-                component_name,
-            ) {
-                Ok(_) => {}
-                Err(err) => eprintln!("Failed to instantiate example: {err:?}"),
+fn print_serialized(value: &impl Serialize, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(value).expect("Failed to serialize output")
+        ),
+        OutputFormat::Yaml => print!(
+            "{}",
+            serde_yaml::to_string(value).expect("Failed to serialize output")
+        ),
+        OutputFormat::Table | OutputFormat::Plain => {
+            unreachable!("{format:?} is not a serialization format")
+        }
+    }
+}
+
+fn language_name(id: &str) -> &'static str {
+    GuestLanguage::from_string(id)
+        .map(|language| language.name())
+        .unwrap_or("unknown")
+}
+
+fn print_examples_table(examples: &[CatalogExample]) {
+    let header = ["NAME", "LANGUAGE", "TIER", "KIND", "DESCRIPTION", "TAGS"];
+    let rows = examples
+        .iter()
+        .map(|example| {
+            [
+                example.name.clone(),
+                language_name(&example.language).to_string(),
+                example.tier.clone(),
+                example.kind.to_string(),
+                example.description.clone(),
+                example.tags.join(", "),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = header.map(|column| column.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let pad = |cell: &str, width: usize| format!("{cell:<width$}");
+
+    println!(
+        "{}",
+        header
+            .iter()
+            .zip(widths)
+            .map(|(column, width)| pad(column, width).bold().to_string())
+            .join("  ")
+            .trim_end()
+    );
+    for row in rows {
+        let cells = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(index, (cell, width))| {
+                let cell = pad(cell, width);
+                match index {
+                    0 => cell.blue().to_string(),
+                    5 => cell.yellow().to_string(),
+                    _ => cell,
+                }
+            })
+            .join("  ");
+        println!("{}", cells.trim_end());
+    }
+}
+
+/// The description is the last column, with tabs and line breaks replaced by spaces
+fn print_examples_plain(examples: &[CatalogExample]) {
+    for example in examples {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            example.name,
+            example.language,
+            example.tier,
+            example.kind,
+            example.tags.join(","),
+            example.description.replace(['\t', '\n', '\r'], " ")
+        );
+    }
+}
+
+fn print_app_examples_tree(catalog: &Catalog) {
+    let description = |name: &str| {
+        catalog
+            .examples
+            .iter()
+            .find(|example| example.name == name)
+            .map(|example| example.description.as_str())
+            .unwrap_or_default()
+    };
+
+    for (language, groups) in &catalog.groups.iter().chunk_by(|group| &group.language) {
+        println!("{}", language_name(language).bold());
+        let groups = groups.collect::<Vec<_>>();
+        for (group_index, group) in groups.iter().enumerate() {
+            let last_group = group_index == groups.len() - 1;
+            println!("{} {}", if last_group { "└─" } else { "├─" }, group.name);
+
+            let templates = group
+                .common
+                .iter()
+                .map(|name| (name, "common"))
+                .chain(group.components.iter().map(|name| (name, "component")))
+                .collect::<Vec<_>>();
+            for (template_index, (name, kind)) in templates.iter().enumerate() {
+                let last_template = template_index == templates.len() - 1;
+                println!(
+                    "{}  {} {} ({}) {}",
+                    if last_group { " " } else { "│" },
+                    if last_template { "└─" } else { "├─" },
+                    name.blue(),
+                    kind,
+                    description(name)
+                );
             }
         }
     }
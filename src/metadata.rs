@@ -0,0 +1,238 @@
+//! Types of the example `metadata.json` files.
+//!
+//! This module is also compiled into the build script (see `build.rs`), which validates the
+//! metadata of every example and generates the static catalog, so it can only depend on crates
+//! which are build dependencies too.
+
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Guest language of the examples, the language directories of `examples` are named after one of
+/// its aliases
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter, Serialize, Deserialize,
+)]
+pub enum GuestLanguage {
+    Rust,
+    Go,
+    C,
+    Zig,
+    JavaScript,
+    TypeScript,
+    CSharp,
+    Swift,
+    Grain,
+    Python,
+    Scala2,
+}
+
+impl GuestLanguage {
+    pub fn from_string(s: impl AsRef<str>) -> Option<GuestLanguage> {
+        let s = s.as_ref().to_lowercase();
+        GuestLanguage::iter().find(|language| language.aliases().contains(&s.as_str()))
+    }
+
+    /// The lowercase names accepted by `from_string`
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            GuestLanguage::Rust => &["rust"],
+            GuestLanguage::Go => &["go"],
+            GuestLanguage::C => &["c", "c++", "cpp"],
+            GuestLanguage::Zig => &["zig"],
+            GuestLanguage::JavaScript => &["js", "javascript"],
+            GuestLanguage::TypeScript => &["ts", "typescript"],
+            GuestLanguage::CSharp => &["c#", "cs", "csharp"],
+            GuestLanguage::Swift => &["swift"],
+            GuestLanguage::Grain => &["grain"],
+            GuestLanguage::Python => &["py", "python"],
+            GuestLanguage::Scala2 => &["scala2"],
+        }
+    }
+}
+
+/// Template metadata, stored in the `metadata.json` file of every example
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(title = "Golem example metadata")]
+pub(crate) struct ExampleMetadata {
+    /// Reference to the JSON schema of the metadata, used only by editors
+    #[serde(rename = "$schema")]
+    pub schema: Option<String>,
+    /// Short description of the example, shown in the example lists
+    pub description: String,
+    /// Free-form tags of the example, shown in the example lists
+    pub tags: Option<Vec<String>>,
+    /// Marks the example as the common template of the given composable app group
+    #[serde(rename = "appCommonGroup")]
+    pub app_common_group: Option<String>,
+    /// The common template is only applied if this file does not exist yet in the target
+    #[serde(rename = "appCommonSkipIfExists")]
+    pub app_common_skip_if_exists: Option<String>,
+    /// Marks the example as a component template of the given composable app group
+    #[serde(rename = "appComponentGroup")]
+    pub app_component_group: Option<String>,
+    /// Copy the WASI adapter of the language tier, defaults to true if `adapterTarget` is set
+    #[serde(rename = "requiresAdapter")]
+    pub requires_adapter: Option<bool>,
+    /// Target directory of the adapter, defaults to `adapters`
+    #[serde(rename = "adapterTarget")]
+    pub adapter_target: Option<String>,
+    /// Copy the Golem host WIT packages
+    #[serde(rename = "requiresGolemHostWIT")]
+    pub requires_golem_host_wit: Option<bool>,
+    /// Copy the WASI WIT packages supported by Golem
+    #[serde(rename = "requiresWASI")]
+    pub requires_wasi: Option<bool>,
+    /// Additional WIT packages to copy, with their transitive dependencies (eg. `wasi:http`)
+    #[serde(rename = "witDeps")]
+    pub wit_deps: Option<Vec<String>>,
+    /// Target directories of the WIT dependencies, defaults to `wit/deps`
+    #[serde(rename = "witDepsPaths")]
+    pub wit_deps_paths: Option<Vec<String>>,
+    /// File and directory names, or paths relative to the example directory, which are not copied
+    pub exclude: Option<Vec<String>>,
+    /// Name of the instructions file, defaults to the language level `INSTRUCTIONS` file
+    pub instructions: Option<String>,
+    /// File names which are copied without applying the template rules
+    #[serde(rename = "transformExclude")]
+    pub transform_exclude: Option<Vec<String>>,
+    /// Apply the template rules, defaults to true
+    pub transform: Option<bool>,
+    /// Additional parameters of the template, replaced the same way as the built-in placeholders
+    pub parameters: Option<Vec<TemplateParameter>>,
+}
+
+/// Parameter declared by a template in addition to the component and package names
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateParameter {
+    /// Name of the parameter, used for providing its value (eg. `--param name=value`)
+    pub name: String,
+    /// Description shown when asking for the value
+    pub description: String,
+    /// Placeholder replaced by the value in the file names and contents
+    pub placeholder: String,
+    /// Default value, the parameter is required if not set
+    pub default: Option<String>,
+}
+
+impl ExampleMetadata {
+    /// Parses the metadata JSON, unknown fields are rejected with a suggestion for the closest known field
+    #[allow(dead_code)] // only used by the build script and the tests
+    pub fn from_json(raw_metadata: &[u8]) -> Result<ExampleMetadata, String> {
+        serde_json::from_slice::<ExampleMetadata>(raw_metadata).map_err(|err| {
+            let message = err.to_string();
+            let suggestion = message
+                .strip_prefix("unknown field `")
+                .and_then(|rest| rest.split_once('`'))
+                .and_then(|(field, _)| closest_field_name(field))
+                .map(|field| format!(", did you mean `{field}`?"));
+            match suggestion {
+                Some(suggestion) => format!("{message}{suggestion}"),
+                None => message,
+            }
+        })
+    }
+
+    pub fn json_schema() -> RootSchema {
+        schema_for!(ExampleMetadata)
+    }
+
+    fn field_names() -> Vec<String> {
+        ExampleMetadata::json_schema()
+            .schema
+            .object
+            .map(|object| object.properties.into_keys().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Checks if an `exclude` entry matches the path relative to the example directory, entries
+/// containing a `/` are matched against the whole path, the others against the file and
+/// directory names on every level
+pub fn exclude_entry_matches(entry: &str, path: &Path) -> bool {
+    if entry.contains('/') {
+        Path::new(entry) == path
+    } else {
+        path.file_name().is_some_and(|name| name == entry)
+    }
+}
+
+fn closest_field_name(field: &str) -> Option<String> {
+    let field = field.to_lowercase();
+    ExampleMetadata::field_names()
+        .into_iter()
+        .map(|known| (edit_distance(&field, &known.to_lowercase()), known))
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Metadata of an example, validated and collected by the build script
+pub(crate) struct ExampleMetadataEntry {
+    /// Name of the language directory under `examples`
+    pub language_dir: &'static str,
+    /// The language of `language_dir`
+    pub language: GuestLanguage,
+    /// Name of the example directory in the language directory
+    pub example_dir: &'static str,
+    pub metadata: ExampleMetadata,
+    /// Instructions with the partials expanded, empty for composable app templates
+    pub instructions: &'static str,
+    /// Paths of the WIT packages declared in the metadata and their transitive dependencies in
+    /// the golem-wit deps directory, None if none of the related fields are set
+    pub declared_wit_deps: Option<Vec<&'static str>>,
+    /// Paths of the WIT packages referenced from the WIT files of the example and their
+    /// transitive dependencies, None if the example has no WIT files
+    pub inferred_wit_deps: Option<Vec<&'static str>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metadata::ExampleMetadata;
+
+    #[test]
+    pub fn metadata_unknown_field_suggestion() {
+        let err = ExampleMetadata::from_json(br#"{"description": "test", "requiresWasi": true}"#)
+            .unwrap_err();
+        assert!(err.contains("unknown field `requiresWasi`"), "{err}");
+        assert!(err.ends_with("did you mean `requiresWASI`?"), "{err}");
+
+        let err =
+            ExampleMetadata::from_json(br#"{"description": "test", "transformExcludes": ["a"]}"#)
+                .unwrap_err();
+        assert!(err.ends_with("did you mean `transformExclude`?"), "{err}");
+
+        let err =
+            ExampleMetadata::from_json(br#"{"description": "test", "colour": "red"}"#).unwrap_err();
+        assert!(!err.contains("did you mean"), "{err}");
+    }
+
+    #[test]
+    pub fn metadata_schema_is_up_to_date() {
+        let schema = serde_json::to_string_pretty(&ExampleMetadata::json_schema()).unwrap();
+        assert_eq!(
+            schema.trim(),
+            include_str!("../schemas/metadata.schema.json").trim(),
+            "The metadata JSON schema is outdated, update it with: cargo run -- schema metadata > schemas/metadata.schema.json"
+        );
+    }
+}
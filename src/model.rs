@@ -1,18 +1,23 @@
+use crate::metadata::exclude_entry_matches;
+pub use crate::metadata::{GuestLanguage, TemplateParameter};
+pub use crate::wit::WitPackageName;
 use fancy_regex::{Match, Regex};
 use inflector::Inflector;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
 use std::fmt::Formatter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::{fmt, io};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-#[derive(
-    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::FromStr, Serialize, Deserialize,
-)]
+/// Name of a component, the casings are built from its parts: the name is split before the
+/// uppercase letters and at the `-`, `_` and `:` separators, and the parts are lowercased,
+/// so eg. `app:MyComponent` is `app-my-component` in kebab case
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String")]
 pub struct ComponentName(String);
 
 static COMPONENT_NAME_SPLIT_REGEX: Lazy<Regex> =
@@ -23,6 +28,31 @@ impl ComponentName {
         ComponentName(name.as_ref().to_string())
     }
 
+    /// Validates the name, which has to start with a letter, and can only contain letters,
+    /// digits and the `-`, `_` and `:` separators
+    pub fn from_string(name: impl AsRef<str>) -> Result<ComponentName, String> {
+        let name = name.as_ref();
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Err(format!(
+                "Invalid component name {name}, it must start with a letter"
+            ));
+        }
+        if let Some(c) = name
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && !matches!(c, '-' | '_' | ':'))
+        {
+            return Err(format!(
+                "Invalid component name {name}, it can only contain letters, digits, '-', '_' and ':', found '{c}'"
+            ));
+        }
+        Ok(ComponentName(name.to_string()))
+    }
+
+    /// The default name of composable app components, the package name in `namespace:name` format
+    pub fn from_package_name(package_name: &PackageName) -> ComponentName {
+        ComponentName(package_name.to_string_with_colon())
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
@@ -69,6 +99,22 @@ impl ComponentName {
     }
 }
 
+impl FromStr for ComponentName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ComponentName::from_string(s)
+    }
+}
+
+impl TryFrom<String> for ComponentName {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        ComponentName::from_string(value)
+    }
+}
+
 impl fmt::Display for ComponentName {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -87,41 +133,7 @@ pub enum ExampleKind {
     },
 }
 
-#[derive(
-    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter, Serialize, Deserialize,
-)]
-pub enum GuestLanguage {
-    Rust,
-    Go,
-    C,
-    Zig,
-    JavaScript,
-    TypeScript,
-    CSharp,
-    Swift,
-    Grain,
-    Python,
-    Scala2,
-}
-
 impl GuestLanguage {
-    pub fn from_string(s: impl AsRef<str>) -> Option<GuestLanguage> {
-        match s.as_ref().to_lowercase().as_str() {
-            "rust" => Some(GuestLanguage::Rust),
-            "go" => Some(GuestLanguage::Go),
-            "c" | "c++" | "cpp" => Some(GuestLanguage::C),
-            "zig" => Some(GuestLanguage::Zig),
-            "js" | "javascript" => Some(GuestLanguage::JavaScript),
-            "ts" | "typescript" => Some(GuestLanguage::TypeScript),
-            "c#" | "cs" | "csharp" => Some(GuestLanguage::CSharp),
-            "swift" => Some(GuestLanguage::Swift),
-            "grain" => Some(GuestLanguage::Grain),
-            "py" | "python" => Some(GuestLanguage::Python),
-            "scala2" => Some(GuestLanguage::Scala2),
-            _ => None,
-        }
-    }
-
     pub fn id(&self) -> String {
         match self {
             GuestLanguage::Rust => "rust".to_string(),
@@ -239,6 +251,8 @@ impl FromStr for GuestLanguageTier {
     }
 }
 
+/// Package name in `namespace:name` format, the casings are built from the namespace and the
+/// name separately, then joined, so eg. `foo:bar-baz` is `foo_bar_baz` in snake case
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct PackageName((String, String));
 
@@ -364,19 +378,81 @@ impl fmt::Display for ComposableAppGroupName {
     }
 }
 
+/// An adapter embedded from golem-wit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adapter {
+    pub tier: GuestLanguageTier,
+    pub path: PathBuf,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdapterStatus {
+    UpToDate,
+    Outdated,
+    Refreshed,
+}
+
+/// Result of checking an adapter found in a generated project against the embedded adapters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterCheck {
+    pub path: PathBuf,
+    pub tier: GuestLanguageTier,
+    pub sha256: String,
+    pub status: AdapterStatus,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum TargetExistsResolveMode {
     Skip,
     MergeOrSkip,
     Fail,
     MergeOrFail,
+    /// Existing files are renamed to `<file name>.bak` before writing the new contents
+    Backup,
 }
 
-pub type MergeContents = Box<dyn FnOnce(&[u8]) -> io::Result<Vec<u8>>>;
+pub type MergeContents = Box<dyn FnOnce(&[u8]) -> crate::error::Result<Vec<u8>>>;
 
 pub enum TargetExistsResolveDecision {
     Skip,
     Merge(MergeContents),
+    Backup,
+}
+
+/// What happens with an already existing file when instantiating an example
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// The existing file is kept
+    Skip,
+    /// The new contents are merged into the existing file
+    Merge,
+    /// The existing file is backed up, then replaced
+    Backup,
+    /// The existing file is replaced, used for outdated adapters
+    Replace,
+    /// The instantiation fails
+    Fail,
+}
+
+impl fmt::Display for ConflictResolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictResolution::Skip => write!(f, "skip"),
+            ConflictResolution::Merge => write!(f, "merge"),
+            ConflictResolution::Backup => write!(f, "backup"),
+            ConflictResolution::Replace => write!(f, "replace"),
+            ConflictResolution::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// A file to be generated, which already exists in the target directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetConflict {
+    /// Path relative to the target directory
+    pub path: PathBuf,
+    pub resolution: ConflictResolution,
 }
 
 #[derive(Debug, Clone)]
@@ -385,6 +461,7 @@ pub struct Example {
     pub kind: ExampleKind,
     pub language: GuestLanguage,
     pub description: String,
+    pub tags: Vec<String>,
     pub example_path: PathBuf,
     pub instructions: String,
     pub adapter_source: Option<PathBuf>,
@@ -394,6 +471,17 @@ pub struct Example {
     pub exclude: HashSet<String>,
     pub transform_exclude: HashSet<String>,
     pub transform: bool,
+    /// Parameters declared by the template, in addition to the component and package names
+    pub parameters: Vec<TemplateParameter>,
+}
+
+impl Example {
+    /// Checks if the path, relative to the example directory, is excluded from the generated files
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude
+            .iter()
+            .any(|entry| exclude_entry_matches(entry, path))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -401,43 +489,43 @@ pub struct ExampleParameters {
     pub component_name: ComponentName,
     pub package_name: PackageName,
     pub target_path: PathBuf,
+    /// Values of the parameters declared by the template, by parameter name
+    pub template_parameters: BTreeMap<String, String>,
 }
 
+/// Declarative description of a composable application, see `instantiate_app`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct ExampleMetadata {
-    pub description: String,
-    #[serde(rename = "appCommonGroup")]
-    pub app_common_group: Option<String>,
-    #[serde(rename = "appCommonSkipIfExists")]
-    pub app_common_skip_if_exists: Option<String>,
-    #[serde(rename = "appComponentGroup")]
-    pub app_component_group: Option<String>,
-    #[serde(rename = "requiresAdapter")]
-    pub requires_adapter: Option<bool>,
-    #[serde(rename = "adapterTarget")]
-    pub adapter_target: Option<String>,
-    #[serde(rename = "requiresGolemHostWIT")]
-    pub requires_golem_host_wit: Option<bool>,
-    #[serde(rename = "requiresWASI")]
-    pub requires_wasi: Option<bool>,
-    #[serde(rename = "witDepsPaths")]
-    pub wit_deps_paths: Option<Vec<String>>,
-    pub exclude: Option<Vec<String>>,
-    pub instructions: Option<String>,
-    #[serde(rename = "transformExclude")]
-    pub transform_exclude: Option<Vec<String>>,
-    pub transform: Option<bool>,
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AppSpec {
+    pub components: Vec<AppComponentSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AppComponentSpec {
+    /// Package name of the component, in namespace:name format
+    pub name: String,
+    /// Name of the component, defaults to the package name, validated by `ComponentName::from_string`
+    pub component_name: Option<String>,
+    /// Language name or alias, as accepted by `GuestLanguage::from_string`
+    pub language: String,
+    pub group: Option<ComposableAppGroupName>,
+    pub template: Option<ExampleName>,
+    /// Values of the parameters declared by the template, by parameter name
+    #[serde(default)]
+    pub parameters: BTreeMap<String, String>,
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::model::{ComponentName, PackageName};
+    use crate::model::{ComponentName, PackageName, WitPackageName};
     use once_cell::sync::Lazy;
 
     static N1: Lazy<ComponentName> = Lazy::new(|| ComponentName::new("my-test-component"));
     static N2: Lazy<ComponentName> = Lazy::new(|| ComponentName::new("MyTestComponent"));
     static N3: Lazy<ComponentName> = Lazy::new(|| ComponentName::new("myTestComponent"));
     static N4: Lazy<ComponentName> = Lazy::new(|| ComponentName::new("my_test_component"));
+    static N5: Lazy<ComponentName> = Lazy::new(|| ComponentName::new("my:test-component"));
 
     #[test]
     pub fn component_name_to_pascal_case() {
@@ -445,6 +533,7 @@ mod tests {
         assert_eq!(N2.to_pascal_case(), "MyTestComponent");
         assert_eq!(N3.to_pascal_case(), "MyTestComponent");
         assert_eq!(N4.to_pascal_case(), "MyTestComponent");
+        assert_eq!(N5.to_pascal_case(), "MyTestComponent");
     }
 
     #[test]
@@ -453,6 +542,7 @@ mod tests {
         assert_eq!(N2.to_camel_case(), "myTestComponent");
         assert_eq!(N3.to_camel_case(), "myTestComponent");
         assert_eq!(N4.to_camel_case(), "myTestComponent");
+        assert_eq!(N5.to_camel_case(), "myTestComponent");
     }
 
     #[test]
@@ -461,6 +551,7 @@ mod tests {
         assert_eq!(N2.to_snake_case(), "my_test_component");
         assert_eq!(N3.to_snake_case(), "my_test_component");
         assert_eq!(N4.to_snake_case(), "my_test_component");
+        assert_eq!(N5.to_snake_case(), "my_test_component");
     }
 
     #[test]
@@ -469,6 +560,56 @@ mod tests {
         assert_eq!(N2.to_kebab_case(), "my-test-component");
         assert_eq!(N3.to_kebab_case(), "my-test-component");
         assert_eq!(N4.to_kebab_case(), "my-test-component");
+        assert_eq!(N5.to_kebab_case(), "my-test-component");
+    }
+
+    #[test]
+    pub fn component_name_validation() {
+        for name in [
+            "my-component",
+            "MyComponent",
+            "my_component2",
+            "app:my-component",
+        ] {
+            assert_eq!(
+                name.parse::<ComponentName>(),
+                Ok(ComponentName::new(name)),
+                "{name}"
+            );
+        }
+        for name in [
+            "",
+            "1component",
+            "-component",
+            "my component",
+            "my/component",
+        ] {
+            assert!(name.parse::<ComponentName>().is_err(), "{name}");
+        }
+        assert!(serde_json::from_str::<ComponentName>("\"my.component\"").is_err());
+    }
+
+    #[test]
+    pub fn wit_package_name_from_string() {
+        let name = WitPackageName::from_string("wasi:io@0.2.0").unwrap();
+        assert_eq!(name.namespace, "wasi");
+        assert_eq!(name.name, "io");
+        assert_eq!(name.version.as_deref(), Some("0.2.0"));
+        assert_eq!(name.to_string(), "wasi:io@0.2.0");
+        assert_eq!(WitPackageName::from_string("wasi"), None);
+        assert_eq!(WitPackageName::from_string("wasi:io:streams"), None);
+    }
+
+    #[test]
+    pub fn wit_package_name_matches() {
+        let unversioned = WitPackageName::from_string("golem:api").unwrap();
+        let versioned = WitPackageName::from_string("golem:api@1.1.0").unwrap();
+        let other_version = WitPackageName::from_string("golem:api@0.2.0").unwrap();
+        assert!(unversioned.matches(&versioned));
+        assert!(unversioned.matches(&other_version));
+        assert!(versioned.matches(&versioned));
+        assert!(!versioned.matches(&other_version));
+        assert!(!versioned.matches(&unversioned));
     }
 
     static P1: Lazy<PackageName> = Lazy::new(|| PackageName::from_string("foo:bar").unwrap());
@@ -479,4 +620,20 @@ mod tests {
         assert_eq!(P1.to_pascal_case(), "FooBar");
         assert_eq!(P2.to_pascal_case(), "FooBarBaz");
     }
+
+    #[test]
+    pub fn package_name_casings() {
+        assert_eq!(P2.to_snake_case(), "foo_bar_baz");
+        assert_eq!(P2.to_kebab_case(), "foo-bar-baz");
+        assert_eq!(P2.to_rust_binding(), "foo::bar_baz");
+        assert_eq!(P2.to_string_with_slash(), "foo/bar-baz");
+    }
+
+    #[test]
+    pub fn component_name_from_package_name() {
+        let component_name = ComponentName::from_package_name(&P2);
+        assert_eq!(component_name.as_str(), "foo:bar-baz");
+        assert_eq!(component_name.to_kebab_case(), "foo-bar-baz");
+        assert_eq!(component_name.to_snake_case(), "foo_bar_baz");
+    }
 }
@@ -0,0 +1,71 @@
+//! Expansion of the `@partial(...)` directives of the template files.
+//!
+//! This module is also compiled into the build script (see `build.rs`), which expands the
+//! partials of the instructions, so it can only depend on crates which are build dependencies too.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Directory under `examples` holding the partials shared between templates
+pub(crate) const PARTIALS_DIR: &str = "partials";
+
+/// Lines containing this directive are replaced by the referenced partial, e.g. `# @partial(gitignore/node.gitignore)`
+pub(crate) static PARTIAL_DIRECTIVE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"@partial\(\s*([^)\s]+)\s*\)").unwrap());
+
+/// Replaces the lines containing partial directives with the referenced partials, recursively
+///
+/// `read_partial` returns the utf8 contents of a partial by its path relative to the examples
+/// directory, or None if it does not exist.
+pub(crate) fn expand_partial_directives(
+    source: &Path,
+    str: &str,
+    read_partial: &impl Fn(&Path) -> Option<String>,
+) -> Result<String, String> {
+    expand(str, &mut vec![source.to_path_buf()], read_partial)
+}
+
+fn expand(
+    str: &str,
+    stack: &mut Vec<PathBuf>,
+    read_partial: &impl Fn(&Path) -> Option<String>,
+) -> Result<String, String> {
+    let mut result = String::with_capacity(str.len());
+    for line in str.split_inclusive('\n') {
+        match PARTIAL_DIRECTIVE_REGEX.captures(line) {
+            Some(captures) => {
+                let partial_path = Path::new(PARTIALS_DIR).join(&captures[1]);
+                if stack.contains(&partial_path) {
+                    return Err(format!(
+                        "Cyclic partial reference: {} -> {}",
+                        stack
+                            .iter()
+                            .map(|path| path.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(" -> "),
+                        partial_path.display()
+                    ));
+                }
+                let partial = read_partial(&partial_path).ok_or_else(|| {
+                    format!(
+                        "Could not find utf8 partial {}, referenced from {}",
+                        partial_path.display(),
+                        stack.last().unwrap().display()
+                    )
+                })?;
+
+                stack.push(partial_path);
+                let partial = expand(&partial, stack, read_partial)?;
+                stack.pop();
+
+                result.push_str(partial.trim_end_matches('\n'));
+                if line.ends_with('\n') {
+                    result.push('\n');
+                }
+            }
+            None => result.push_str(line),
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,347 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::lock::{read_template_lock, write_template_lock, TemplateLock, TEMPLATE_LOCK_FILE_NAME};
+use crate::model::{ComponentName, PackageName};
+use crate::{render_template_file, sha256, template_files};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Changes of renaming a component or package, see `plan_rename`
+#[derive(Debug, Clone)]
+pub struct RenamePlan {
+    /// Old and new component and package names, of every renamed template
+    pub names: Vec<(String, String)>,
+    pub files: Vec<RenamedFile>,
+    lock: TemplateLock,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenamedFile {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    /// New path relative to the project root, same as `path` if the file is not moved
+    pub new_path: PathBuf,
+    /// New contents, None if only the path changes
+    contents: Option<Vec<u8>>,
+}
+
+impl RenamedFile {
+    pub fn is_moved(&self) -> bool {
+        self.path != self.new_path
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.contents.is_some()
+    }
+}
+
+/// Plans renaming a component and/or its package in a generated project, using the parameters
+/// recorded in its lock file, without writing anything
+///
+/// `component` selects the renamed component by its recorded name, it can be omitted if the
+/// project has only one component. The templates of the component are rendered with the old and
+/// the new names, and the difference is three-way merged into the files generated by them, so
+/// only the places the templates substituted the names are changed, and user edits are kept. If
+/// a user edit conflicts with the renaming, nothing is planned and a merge error is returned.
+/// Components named after their package are renamed with the package.
+pub fn plan_rename(
+    project_path: &Path,
+    component: Option<&str>,
+    new_component_name: Option<&ComponentName>,
+    new_package_name: Option<&PackageName>,
+) -> Result<RenamePlan> {
+    let mut lock = read_template_lock(project_path)?.ok_or_else(|| {
+        Error::validation(format!(
+            "No {TEMPLATE_LOCK_FILE_NAME} file, only projects generated with a template lock file can be renamed"
+        ))
+        .with_path(project_path)
+    })?;
+    if new_component_name.is_none() && new_package_name.is_none() {
+        return Err(Error::validation(
+            "Nothing to rename, a new component name or package name has to be provided"
+                .to_string(),
+        ));
+    }
+
+    let components = lock
+        .templates
+        .iter()
+        .map(|entry| entry.component_name.as_str())
+        .unique()
+        .collect::<Vec<_>>();
+    let component = match (component, components.as_slice()) {
+        (Some(component), _) if components.contains(&component) => component.to_string(),
+        (None, [component]) => component.to_string(),
+        (Some(component), _) => {
+            return Err(Error::validation(format!(
+                "Component {} is not recorded in the template lock, recorded components: {}",
+                component,
+                components.join(", ")
+            )))
+        }
+        (None, _) => {
+            return Err(Error::validation(format!(
+                "The project has multiple components, select one of them: {}",
+                components.join(", ")
+            )))
+        }
+    };
+
+    let mut names = vec![];
+    let mut renamed_files = vec![];
+    for entry in lock
+        .templates
+        .iter_mut()
+        .filter(|entry| entry.component_name == component)
+    {
+        let example = entry.find_example()?;
+        let old_parameters = entry.example_parameters(project_path)?;
+        let mut new_parameters = old_parameters.clone();
+        if let Some(package_name) = new_package_name {
+            if entry.component_name == entry.package_name {
+                new_parameters.component_name = ComponentName::from_package_name(package_name);
+            }
+            new_parameters.package_name = package_name.clone();
+        }
+        if let Some(component_name) = new_component_name {
+            new_parameters.component_name = component_name.clone();
+        }
+        for (old, new) in [
+            (
+                old_parameters.component_name.to_string(),
+                new_parameters.component_name.to_string(),
+            ),
+            (
+                old_parameters.package_name.to_string_with_colon(),
+                new_parameters.package_name.to_string_with_colon(),
+            ),
+        ] {
+            if old != new && !names.contains(&(old.clone(), new.clone())) {
+                names.push((old, new));
+            }
+        }
+
+        // The file lists only differ in the transformed names, the files are matched by source
+        let new_files = template_files(&example, &new_parameters)?
+            .into_iter()
+            .map(|file| (file.source.clone(), file))
+            .collect::<BTreeMap<_, _>>();
+        let mut files = BTreeMap::new();
+        let mut base = BTreeMap::new();
+        for old_file in template_files(&example, &old_parameters)? {
+            let Some(new_file) = new_files.get(&old_file.source) else {
+                continue;
+            };
+            let path = old_file.target.clone();
+            let new_path = new_file.target.clone();
+            let old_output = render_template_file(&example, &old_file, &old_parameters)?;
+            let new_output = render_template_file(&example, new_file, &new_parameters)?;
+            if let Some(recorded) = entry.base.get(&path) {
+                base.insert(
+                    new_path.clone(),
+                    rename_text(recorded.as_bytes(), &old_output, &new_output)
+                        .and_then(|text| String::from_utf8(text).ok())
+                        .unwrap_or_else(|| String::from_utf8_lossy(&new_output).to_string()),
+                );
+            }
+            let Some(hash) = entry.files.get(&path) else {
+                continue;
+            };
+
+            let source = project_path.join(&path);
+            if !source.is_file() {
+                files.insert(new_path, hash.clone());
+                continue;
+            }
+            let current = fs::read(&source).with_path(&source)?;
+            let contents =
+                rename_text(&current, &old_output, &new_output).ok_or_else(|| Error::Merge {
+                    path: source.clone(),
+                    message: "the changes of the file conflict with renaming, nothing was renamed"
+                        .to_string(),
+                })?;
+            let contents = (contents != current).then_some(contents);
+            files.insert(
+                new_path.clone(),
+                contents.as_deref().map(sha256).unwrap_or(hash.clone()),
+            );
+            if path != new_path || contents.is_some() {
+                renamed_files.push(RenamedFile {
+                    path,
+                    new_path,
+                    contents,
+                });
+            }
+        }
+        // Files which are not template files (adapter, WIT dependencies) do not depend on the names
+        for (path, hash) in &entry.files {
+            if !files.contains_key(path) && !renamed_files.iter().any(|file| &file.path == path) {
+                files.insert(path.clone(), hash.clone());
+            }
+        }
+
+        entry.component_name = new_parameters.component_name.to_string();
+        entry.package_name = new_parameters.package_name.to_string_with_colon();
+        entry.files = files;
+        entry.base = base;
+    }
+
+    for file in renamed_files.iter().filter(|file| file.is_moved()) {
+        if project_path.join(&file.new_path).exists()
+            && !renamed_files
+                .iter()
+                .any(|other| other.path == file.new_path)
+        {
+            return Err(Error::Conflict {
+                path: project_path.join(&file.new_path),
+            });
+        }
+    }
+
+    Ok(RenamePlan {
+        names,
+        files: renamed_files,
+        lock,
+    })
+}
+
+/// Renames the files of the plan and rewrites their contents, then updates the lock file
+pub fn apply_rename(project_path: &Path, plan: &RenamePlan) -> Result<()> {
+    let mut contents = vec![];
+    for file in &plan.files {
+        let source = project_path.join(&file.path);
+        contents.push(match &file.contents {
+            Some(contents) => contents.clone(),
+            None => fs::read(&source).with_path(&source)?,
+        });
+    }
+    for file in plan.files.iter().filter(|file| file.is_moved()) {
+        let source = project_path.join(&file.path);
+        fs::remove_file(&source).with_path(&source)?;
+    }
+    for (file, contents) in plan.files.iter().zip(contents) {
+        let target = project_path.join(&file.new_path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_path(parent)?;
+        }
+        fs::write(&target, contents).with_path(&target)?;
+    }
+    for file in plan.files.iter().filter(|file| file.is_moved()) {
+        remove_empty_parents(project_path, &file.path)?;
+    }
+    write_template_lock(project_path, &plan.lock)
+}
+
+/// Applies the difference of the template output rendered with the old and the new names to the
+/// contents with a three-way merge, None if the contents cannot be merged
+fn rename_text(contents: &[u8], old_output: &[u8], new_output: &[u8]) -> Option<Vec<u8>> {
+    if contents == old_output || old_output == new_output {
+        return Some(match contents == old_output {
+            true => new_output.to_vec(),
+            false => contents.to_vec(),
+        });
+    }
+    diffy::merge(
+        std::str::from_utf8(old_output).ok()?,
+        std::str::from_utf8(contents).ok()?,
+        std::str::from_utf8(new_output).ok()?,
+    )
+    .ok()
+    .map(String::into_bytes)
+}
+
+/// Removes the parent directories of the path which became empty, up to the project root
+fn remove_empty_parents(project_path: &Path, path: &Path) -> Result<()> {
+    for parent in path.ancestors().skip(1) {
+        if parent.as_os_str().is_empty() {
+            break;
+        }
+        let dir = project_path.join(parent);
+        if !dir.is_dir() || fs::read_dir(&dir).with_path(&dir)?.next().is_some() {
+            break;
+        }
+        fs::remove_dir(&dir).with_path(&dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::diff_project;
+    use crate::lock::read_template_lock;
+    use crate::model::{ComponentName, PackageName};
+    use crate::rename::{apply_rename, plan_rename};
+    use crate::test_fixtures::TestProject;
+    use std::path::Path;
+
+    #[test]
+    pub fn rename_component_and_package() {
+        let project = TestProject::generated("rust-default");
+        let target_path = project.path();
+
+        let plan = plan_rename(
+            target_path,
+            None,
+            Some(&ComponentName::new("shop-cart")),
+            Some(&PackageName::from_string("acme:shop").unwrap()),
+        )
+        .unwrap();
+        assert!(plan
+            .names
+            .contains(&("my-component".to_string(), "shop-cart".to_string())));
+        assert!(plan
+            .names
+            .contains(&("my:pack".to_string(), "acme:shop".to_string())));
+        assert!(plan
+            .files
+            .iter()
+            .any(|file| file.path == Path::new("wit/my-component.wit")
+                && file.new_path == Path::new("wit/shop-cart.wit")));
+        assert!(target_path.join("wit/my-component.wit").exists());
+
+        apply_rename(target_path, &plan).unwrap();
+        assert!(!target_path.join("wit/my-component.wit").exists());
+        let wit = std::fs::read_to_string(target_path.join("wit/shop-cart.wit")).unwrap();
+        assert!(wit.contains("package acme:shop;"));
+        let lock = read_template_lock(target_path).unwrap().unwrap();
+        assert_eq!(lock.templates[0].component_name, "shop-cart");
+        assert_eq!(lock.templates[0].package_name, "acme:shop");
+        // The renamed project is the same as the one generated with the new names
+        assert!(diff_project(target_path).unwrap().is_empty());
+    }
+
+    #[test]
+    pub fn rename_common_word_component_keeps_package_and_edits() {
+        let mut project = TestProject::new("rust-default");
+        project.parameters.component_name = ComponentName::new("component");
+        project.parameters.package_name = PackageName::from_string("component:store").unwrap();
+        project.instantiate();
+        let target_path = project.path();
+        let lib_path = target_path.join("src/lib.rs");
+        let lib = std::fs::read_to_string(&lib_path).unwrap();
+        let lib = format!("// The component keeps its state in memory\n{lib}");
+        std::fs::write(&lib_path, &lib).unwrap();
+
+        let plan = plan_rename(target_path, None, Some(&ComponentName::new("api")), None).unwrap();
+        assert_eq!(
+            plan.names,
+            vec![("component".to_string(), "api".to_string())]
+        );
+        apply_rename(target_path, &plan).unwrap();
+
+        let cargo_toml = std::fs::read_to_string(target_path.join("Cargo.toml")).unwrap();
+        assert!(cargo_toml.contains("name = \"api\""));
+        assert!(cargo_toml.contains("[package.metadata.component.target]"));
+        let wit = std::fs::read_to_string(target_path.join("wit/api.wit")).unwrap();
+        assert!(wit.contains("package component:store;"));
+        assert!(wit.contains("world api {"));
+        assert_eq!(std::fs::read_to_string(&lib_path).unwrap(), lib);
+        let lock = read_template_lock(target_path).unwrap().unwrap();
+        assert_eq!(lock.templates[0].component_name, "api");
+        assert_eq!(lock.templates[0].package_name, "component:store");
+        // Only the user edit differs from the project generated with the new name
+        let diff = diff_project(target_path).unwrap();
+        assert_eq!(diff.len(), 1);
+    }
+}
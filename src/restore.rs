@@ -0,0 +1,190 @@
+use crate::check_template_parameters;
+use crate::error::{Error, IoResultExt, Result};
+use crate::lock::{generated_contents, read_template_lock, TEMPLATE_LOCK_FILE_NAME};
+use crate::model::{Example, ExampleParameters};
+use glob::Pattern;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RestoreStatus {
+    /// Missing file, created from the template output
+    Restored,
+    /// Existing file differing from the template output, replaced as requested
+    Overwritten,
+    /// Existing file differing from the template output, left untouched
+    Skipped,
+}
+
+impl fmt::Display for RestoreStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RestoreStatus::Restored => write!(f, "restored"),
+            RestoreStatus::Overwritten => write!(f, "overwritten"),
+            RestoreStatus::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreChange {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    pub status: RestoreStatus,
+}
+
+/// Regenerates the missing files of a generated project from the templates recorded in its lock
+/// file
+///
+/// With `patterns` only the files matching one of the glob patterns are restored, a pattern also
+/// matches all files below the directories it matches. Existing files differing from the template
+/// output are only replaced with `overwrite`. With `dry_run` only the changes are returned,
+/// nothing is written.
+pub fn restore_project(
+    project_path: &Path,
+    patterns: &[Pattern],
+    overwrite: bool,
+    dry_run: bool,
+) -> Result<Vec<RestoreChange>> {
+    let lock = read_template_lock(project_path)?.ok_or_else(|| {
+        Error::validation(format!(
+            "No {TEMPLATE_LOCK_FILE_NAME} file, the example and its parameters have to be provided"
+        ))
+        .with_path(project_path)
+    })?;
+
+    let mut generated = BTreeMap::new();
+    for entry in &lock.templates {
+        let example = entry.find_example()?;
+        let parameters = entry.example_parameters(project_path)?;
+        generated.extend(generated_contents(&example, &parameters)?);
+    }
+    restore_contents(project_path, &generated, patterns, overwrite, dry_run)
+}
+
+/// Regenerates the missing files of the project at the target path of the parameters from the
+/// example, see `restore_project`
+pub fn restore_example(
+    example: &Example,
+    parameters: &ExampleParameters,
+    patterns: &[Pattern],
+    overwrite: bool,
+    dry_run: bool,
+) -> Result<Vec<RestoreChange>> {
+    check_template_parameters(example, parameters)?;
+    restore_contents(
+        &parameters.target_path,
+        &generated_contents(example, parameters)?,
+        patterns,
+        overwrite,
+        dry_run,
+    )
+}
+
+fn restore_contents(
+    project_path: &Path,
+    generated: &BTreeMap<PathBuf, Vec<u8>>,
+    patterns: &[Pattern],
+    overwrite: bool,
+    dry_run: bool,
+) -> Result<Vec<RestoreChange>> {
+    let mut changes = vec![];
+    for (path, contents) in generated {
+        if !patterns.is_empty() && !patterns.iter().any(|pattern| matches(pattern, path)) {
+            continue;
+        }
+        let target = project_path.join(path);
+        let status = if !target.exists() {
+            RestoreStatus::Restored
+        } else if fs::read(&target).with_path(&target)? == *contents {
+            continue;
+        } else if overwrite {
+            RestoreStatus::Overwritten
+        } else {
+            RestoreStatus::Skipped
+        };
+        if !dry_run && status != RestoreStatus::Skipped {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).with_path(parent)?;
+            }
+            fs::write(&target, contents).with_path(&target)?;
+        }
+        changes.push(RestoreChange {
+            path: path.clone(),
+            status,
+        });
+    }
+    Ok(changes)
+}
+
+/// Matches the path, or one of its parent directories, with the pattern
+fn matches(pattern: &Pattern, path: &Path) -> bool {
+    path.ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .any(|ancestor| pattern.matches_path(ancestor))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::restore::{restore_project, RestoreChange, RestoreStatus};
+    use crate::test_fixtures::TestProject;
+    use glob::Pattern;
+    use std::path::PathBuf;
+
+    #[test]
+    pub fn restore_missing_files() {
+        let project = TestProject::generated("go-default");
+        let target_path = project.path();
+        assert!(restore_project(target_path, &[], false, false)
+            .unwrap()
+            .is_empty());
+
+        std::fs::remove_dir_all(target_path.join("wit/deps")).unwrap();
+        std::fs::remove_file(target_path.join("go.mod")).unwrap();
+        std::fs::write(target_path.join("main.go"), "modified").unwrap();
+
+        let patterns = [Pattern::new("wit/deps").unwrap()];
+        let changes = restore_project(target_path, &patterns, false, true).unwrap();
+        assert!(!changes.is_empty());
+        assert!(changes.iter().all(|change| {
+            change.path.starts_with("wit/deps") && change.status == RestoreStatus::Restored
+        }));
+        assert!(!target_path.join("wit/deps").exists());
+
+        let changes = restore_project(target_path, &[], false, false).unwrap();
+        assert!(changes.contains(&RestoreChange {
+            path: PathBuf::from("go.mod"),
+            status: RestoreStatus::Restored
+        }));
+        assert!(changes.contains(&RestoreChange {
+            path: PathBuf::from("main.go"),
+            status: RestoreStatus::Skipped
+        }));
+        assert!(target_path.join("go.mod").exists());
+        assert!(target_path.join("wit/deps/io/streams.wit").exists());
+        assert_eq!(
+            std::fs::read_to_string(target_path.join("main.go")).unwrap(),
+            "modified"
+        );
+
+        let patterns = [Pattern::new("*.go").unwrap()];
+        let changes = restore_project(target_path, &patterns, true, false).unwrap();
+        assert_eq!(
+            changes,
+            vec![RestoreChange {
+                path: PathBuf::from("main.go"),
+                status: RestoreStatus::Overwritten
+            }]
+        );
+        assert_ne!(
+            std::fs::read_to_string(target_path.join("main.go")).unwrap(),
+            "modified"
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::lock::{read_template_lock, update_template_lock, TEMPLATE_LOCK_FILE_NAME};
+use crate::model::{Example, ExampleParameters};
+use crate::wit::parse_package_declaration;
+use crate::{
+    adapter_target, all_adapters, get_adapter_contents, sha256, wit_deps_targets, WIT, WIT_PACKAGES,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WitSyncStatus {
+    /// Same contents as the embedded package or adapter
+    UpToDate,
+    /// Differs from the embedded package or adapter, replaced
+    Outdated,
+    /// Missing from the project, copied
+    Missing,
+    /// golem-wit package the templates do not use anymore, deleted
+    Extra,
+}
+
+impl WitSyncStatus {
+    /// The project has to be changed to match the embedded golem-wit bundle
+    pub fn needs_sync(&self) -> bool {
+        *self != WitSyncStatus::UpToDate
+    }
+}
+
+impl fmt::Display for WitSyncStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WitSyncStatus::UpToDate => write!(f, "up to date"),
+            WitSyncStatus::Outdated => write!(f, "outdated"),
+            WitSyncStatus::Missing => write!(f, "missing"),
+            WitSyncStatus::Extra => write!(f, "extra"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WitSyncChange {
+    /// Path of the package directory or the adapter, relative to the project root
+    pub path: PathBuf,
+    /// Name of the WIT package, `None` for adapters
+    pub package: Option<String>,
+    pub status: WitSyncStatus,
+}
+
+/// Synchronizes the vendored WIT packages and adapters of a generated project with the embedded
+/// golem-wit bundle, using the templates recorded in its lock file
+///
+/// Every WIT deps directory of the templates is checked: missing and outdated packages are
+/// copied from the bundle, and golem-wit packages the templates do not use anymore are deleted,
+/// other packages are left untouched. Adapters are replaced if they differ from the embedded
+/// adapter of their tier. The hashes of the lock file are updated. With `dry_run` only the
+/// results are returned, nothing is written.
+pub fn sync_project_wit(project_path: &Path, dry_run: bool) -> Result<Vec<WitSyncChange>> {
+    let lock = read_template_lock(project_path)?.ok_or_else(|| {
+        Error::validation(format!(
+            "No {TEMPLATE_LOCK_FILE_NAME} file, the example has to be provided"
+        ))
+        .with_path(project_path)
+    })?;
+
+    let mut templates = vec![];
+    for entry in &lock.templates {
+        templates.push(entry.find_example()?);
+    }
+    let changes = sync_wit(project_path, &templates, dry_run)?;
+
+    if !dry_run && changes.iter().any(|change| change.status.needs_sync()) {
+        for (mut entry, example) in lock.templates.into_iter().zip(&templates) {
+            let dependencies = dependency_paths(example);
+            let synced = changes
+                .iter()
+                .filter(|change| change.status.needs_sync())
+                .map(|change| &change.path)
+                .filter(|path| {
+                    dependencies.contains(*path)
+                        || entry.files.keys().any(|file| file.starts_with(path))
+                })
+                .collect::<Vec<_>>();
+            entry
+                .files
+                .retain(|path, _| !synced.iter().any(|synced| path.starts_with(synced)));
+            for path in synced {
+                for (file, contents) in project_files(&project_path.join(path))? {
+                    entry.files.insert(path.join(file), sha256(&contents));
+                }
+            }
+            update_template_lock(project_path, entry)?;
+        }
+    }
+    Ok(changes)
+}
+
+/// Synchronizes the vendored WIT packages and adapters of the project at the target path of the
+/// parameters with the embedded golem-wit bundle, see `sync_project_wit`
+pub fn sync_example_wit(
+    example: &Example,
+    parameters: &ExampleParameters,
+    dry_run: bool,
+) -> Result<Vec<WitSyncChange>> {
+    sync_wit(
+        &parameters.target_path,
+        std::slice::from_ref(example),
+        dry_run,
+    )
+}
+
+fn sync_wit(
+    project_path: &Path,
+    templates: &[Example],
+    dry_run: bool,
+) -> Result<Vec<WitSyncChange>> {
+    // Bundle paths of the packages, by WIT deps directory relative to the project root
+    let mut packages = BTreeMap::<PathBuf, BTreeSet<PathBuf>>::new();
+    // Embedded adapter, by adapter path relative to the project root
+    let mut adapters = BTreeMap::<PathBuf, PathBuf>::new();
+    for example in templates {
+        if !example.wit_deps.is_empty() {
+            for target in wit_deps_targets(example) {
+                packages
+                    .entry(target)
+                    .or_default()
+                    .extend(example.wit_deps.iter().cloned());
+            }
+        }
+        if let (Some(source), Some(target)) =
+            (example.adapter_source.clone(), adapter_target(example))
+        {
+            adapters.entry(target).or_insert(source);
+        }
+    }
+
+    let mut changes = vec![];
+    for (deps_path, bundle_paths) in &packages {
+        let package_names = bundle_paths
+            .iter()
+            .filter_map(|path| path.file_name())
+            .collect::<BTreeSet<_>>();
+        for bundle_path in bundle_paths {
+            let path = deps_path.join(bundle_path.file_name().unwrap());
+            let target = project_path.join(&path);
+            let expected = bundle_files(bundle_path)?;
+            let status = if !target.is_dir() {
+                WitSyncStatus::Missing
+            } else if project_files(&target)? != expected {
+                WitSyncStatus::Outdated
+            } else {
+                WitSyncStatus::UpToDate
+            };
+            if !dry_run && status.needs_sync() {
+                if target.exists() {
+                    fs::remove_dir_all(&target).with_path(&target)?;
+                }
+                fs::create_dir_all(&target).with_path(&target)?;
+                for (file_name, contents) in &expected {
+                    let file = target.join(file_name);
+                    fs::write(&file, contents).with_path(&file)?;
+                }
+            }
+            changes.push(WitSyncChange {
+                path,
+                package: WIT_PACKAGES
+                    .find_by_path(bundle_path)
+                    .map(|package| package.name.to_string()),
+                status,
+            });
+        }
+
+        let deps_dir = project_path.join(deps_path);
+        if !deps_dir.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&deps_dir).with_path(&deps_dir)? {
+            let entry = entry.with_path(&deps_dir)?;
+            if !entry.file_type().with_path(entry.path())?.is_dir()
+                || package_names.contains(entry.file_name().as_os_str())
+            {
+                continue;
+            }
+            let Some(name) = project_files(&entry.path())?
+                .values()
+                .find_map(|contents| parse_package_declaration(&String::from_utf8_lossy(contents)))
+            else {
+                continue;
+            };
+            let is_golem_wit_package = WIT_PACKAGES.packages.iter().any(|package| {
+                package.name.namespace == name.namespace && package.name.name == name.name
+            });
+            if !is_golem_wit_package {
+                continue;
+            }
+            if !dry_run {
+                fs::remove_dir_all(entry.path()).with_path(entry.path())?;
+            }
+            changes.push(WitSyncChange {
+                path: deps_path.join(entry.file_name()),
+                package: Some(name.to_string()),
+                status: WitSyncStatus::Extra,
+            });
+        }
+    }
+
+    let embedded_adapters = all_adapters();
+    for (path, source) in &adapters {
+        let target = project_path.join(path);
+        let status = if !target.is_file() {
+            WitSyncStatus::Missing
+        } else {
+            let hash = sha256(&fs::read(&target).with_path(&target)?);
+            let tier = source.parent().unwrap_or(Path::new(""));
+            if embedded_adapters
+                .iter()
+                .any(|adapter| adapter.path.parent() == Some(tier) && adapter.sha256 == hash)
+            {
+                WitSyncStatus::UpToDate
+            } else {
+                WitSyncStatus::Outdated
+            }
+        };
+        if !dry_run && status.needs_sync() {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).with_path(parent)?;
+            }
+            fs::write(&target, get_adapter_contents(source)?).with_path(&target)?;
+        }
+        changes.push(WitSyncChange {
+            path: path.clone(),
+            package: None,
+            status,
+        });
+    }
+
+    Ok(changes)
+}
+
+/// Paths of the WIT package directories and the adapter the example vendors, relative to the
+/// project root
+fn dependency_paths(example: &Example) -> BTreeSet<PathBuf> {
+    let mut result = BTreeSet::new();
+    if !example.wit_deps.is_empty() {
+        for target in wit_deps_targets(example) {
+            for wit_dep in &example.wit_deps {
+                result.insert(target.join(wit_dep.file_name().unwrap()));
+            }
+        }
+    }
+    result.extend(adapter_target(example));
+    result
+}
+
+/// Files of an embedded WIT package, by file name
+fn bundle_files(bundle_path: &Path) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let dir = WIT.get_dir(bundle_path).ok_or_else(|| {
+        Error::catalog("Could not find WIT package in the golem-wit bundle").with_path(bundle_path)
+    })?;
+    Ok(dir
+        .files()
+        .map(|file| {
+            (
+                PathBuf::from(file.path().file_name().unwrap()),
+                file.contents().to_vec(),
+            )
+        })
+        .collect())
+}
+
+/// Files of a directory, or the file itself, by path relative to the path
+fn project_files(path: &Path) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut result = BTreeMap::new();
+    if path.is_file() {
+        result.insert(PathBuf::new(), fs::read(path).with_path(path)?);
+    } else if path.is_dir() {
+        for entry in fs::read_dir(path).with_path(path)? {
+            let entry = entry.with_path(path)?;
+            if entry.file_type().with_path(entry.path())?.is_file() {
+                result.insert(
+                    PathBuf::from(entry.file_name()),
+                    fs::read(entry.path()).with_path(entry.path())?,
+                );
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lock::read_template_lock;
+    use crate::sha256;
+    use crate::sync::{sync_project_wit, WitSyncStatus};
+    use crate::test_fixtures::TestProject;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    pub fn sync_outdated_missing_and_extra() {
+        let project = TestProject::generated("go-default");
+        let target_path = project.path();
+        assert!(sync_project_wit(target_path, true)
+            .unwrap()
+            .iter()
+            .all(|change| change.status == WitSyncStatus::UpToDate));
+
+        let deps = target_path.join("wit/deps");
+        std::fs::remove_dir_all(deps.join("io")).unwrap();
+        std::fs::write(deps.join("clocks/monotonic-clock.wit"), "stale").unwrap();
+        std::fs::create_dir_all(deps.join("old-golem")).unwrap();
+        std::fs::write(
+            deps.join("old-golem/golem.wit"),
+            "package golem:api@0.2.0;\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(deps.join("user")).unwrap();
+        std::fs::write(deps.join("user/user.wit"), "package my:user;\n").unwrap();
+        std::fs::write(
+            target_path.join("adapters/tier1/wasi_snapshot_preview1.wasm"),
+            "stale",
+        )
+        .unwrap();
+
+        let changes = sync_project_wit(target_path, false).unwrap();
+        let status = |path: &str| {
+            changes
+                .iter()
+                .find(|change| change.path == Path::new(path))
+                .map(|change| change.status)
+        };
+        assert_eq!(status("wit/deps/io"), Some(WitSyncStatus::Missing));
+        assert_eq!(status("wit/deps/clocks"), Some(WitSyncStatus::Outdated));
+        assert_eq!(status("wit/deps/old-golem"), Some(WitSyncStatus::Extra));
+        assert_eq!(status("wit/deps/user"), None);
+        assert_eq!(
+            status("adapters/tier1/wasi_snapshot_preview1.wasm"),
+            Some(WitSyncStatus::Outdated)
+        );
+
+        assert!(deps.join("io/streams.wit").exists());
+        assert!(!deps.join("old-golem").exists());
+        assert!(deps.join("user/user.wit").exists());
+        let lock = read_template_lock(target_path).unwrap().unwrap();
+        let clock = deps.join("clocks/monotonic-clock.wit");
+        assert_eq!(
+            lock.templates[0]
+                .files
+                .get(&PathBuf::from("wit/deps/clocks/monotonic-clock.wit")),
+            Some(&sha256(&std::fs::read(&clock).unwrap()))
+        );
+        assert!(sync_project_wit(target_path, false)
+            .unwrap()
+            .iter()
+            .all(|change| change.status == WitSyncStatus::UpToDate));
+    }
+}
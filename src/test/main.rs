@@ -10,7 +10,7 @@ use golem_examples::{
 };
 use nanoid::nanoid;
 use regex::Regex;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::io;
 use std::path::PathBuf;
 use std::process::exit;
@@ -134,8 +134,11 @@ pub fn main() -> io::Result<()> {
                         default_examples.common.as_ref(),
                         default_component_example,
                         &target_path,
+                        &ComponentName::from_package_name(&package_name),
                         &package_name,
-                    )?
+                        &BTreeMap::new(),
+                    )
+                    .map_err(io::Error::other)?;
                 }
             }
 
@@ -195,6 +198,7 @@ fn test_example(
         component_name: component_name.clone(),
         package_name,
         target_path: target_path.join(component_name.as_str()),
+        template_parameters: BTreeMap::new(),
     };
 
     let run = |command: &str, args: Vec<&str>| -> Result<(), String> {
@@ -0,0 +1,68 @@
+//! Fixtures shared by the tests of the library modules
+
+use crate::model::{
+    ComponentName, Example, ExampleParameters, PackageName, TargetExistsResolveMode,
+};
+use crate::{all_standalone_examples, instantiate_example};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// The embedded standalone example with the given name
+pub(crate) fn example(name: &str) -> Example {
+    all_standalone_examples()
+        .into_iter()
+        .find(|example| example.name.as_string() == name)
+        .unwrap_or_else(|| panic!("{name} is not an embedded standalone example"))
+}
+
+/// Parameters of the `my-component` component in the `my:pack` package
+pub(crate) fn parameters(target_path: &Path) -> ExampleParameters {
+    ExampleParameters {
+        component_name: ComponentName::new("my-component"),
+        package_name: PackageName::from_string("my:pack").unwrap(),
+        target_path: target_path.to_path_buf(),
+        template_parameters: BTreeMap::new(),
+    }
+}
+
+/// A project directory of an example in a temporary directory, which is deleted when the fixture
+/// is dropped
+pub(crate) struct TestProject {
+    _dir: TempDir,
+    pub example: Example,
+    pub parameters: ExampleParameters,
+}
+
+impl TestProject {
+    /// The project is not generated, and its directory does not exist yet
+    pub fn new(example_name: &str) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let parameters = parameters(&dir.path().join("project"));
+        TestProject {
+            _dir: dir,
+            example: example(example_name),
+            parameters,
+        }
+    }
+
+    /// The project generated with the default parameters
+    pub fn generated(example_name: &str) -> Self {
+        let project = Self::new(example_name);
+        project.instantiate();
+        project
+    }
+
+    pub fn instantiate(&self) {
+        instantiate_example(
+            &self.example,
+            &self.parameters,
+            TargetExistsResolveMode::Fail,
+        )
+        .unwrap();
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.parameters.target_path
+    }
+}
@@ -0,0 +1,370 @@
+use crate::error::{Error, IoResultExt, Result};
+use crate::lock::{
+    generated_contents, read_template_lock, template_lock_entry, update_template_lock,
+    TEMPLATE_LOCK_FILE_NAME,
+};
+use crate::sha256;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpgradeStatus {
+    /// New template file, created
+    Added,
+    /// Unmodified file, replaced with the new template output
+    Updated,
+    /// Modified file, the template changes were merged cleanly
+    Merged,
+    /// Modified file with conflicting changes, conflict markers were written into the file
+    Conflict,
+    /// Modified file which cannot be merged, or existing file without recorded template output,
+    /// the new template output was written next to it with a `.rej` extension
+    Rejected,
+    /// Unmodified file removed from the template, deleted
+    Removed,
+    /// Modified file removed from the template, or template file deleted by the user, kept
+    Kept,
+}
+
+impl UpgradeStatus {
+    /// The file needs manual resolution
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, UpgradeStatus::Conflict | UpgradeStatus::Rejected)
+    }
+}
+
+impl fmt::Display for UpgradeStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UpgradeStatus::Added => write!(f, "added"),
+            UpgradeStatus::Updated => write!(f, "updated"),
+            UpgradeStatus::Merged => write!(f, "merged"),
+            UpgradeStatus::Conflict => write!(f, "conflict"),
+            UpgradeStatus::Rejected => write!(f, "rejected"),
+            UpgradeStatus::Removed => write!(f, "removed"),
+            UpgradeStatus::Kept => write!(f, "kept"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeChange {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    pub status: UpgradeStatus,
+}
+
+enum UpgradeAction {
+    Write(Vec<u8>),
+    WriteRejected(Vec<u8>),
+    Remove,
+    None,
+}
+
+/// Upgrades the files of a generated project to the current templates, using the template lock
+/// file of the project
+///
+/// The new template output is three-way merged into the files, with the recorded template
+/// output as the base: unmodified files are replaced, clean changes are merged, conflicting
+/// changes are left with conflict markers, or in `.rej` files if the contents cannot be merged.
+/// The lock file is updated to the new template output. With `dry_run` only the changes are
+/// returned, nothing is written.
+pub fn upgrade_project(project_path: &Path, dry_run: bool) -> Result<Vec<UpgradeChange>> {
+    let lock = read_template_lock(project_path)?.ok_or_else(|| {
+        Error::validation(format!(
+            "No {TEMPLATE_LOCK_FILE_NAME} file, only projects generated with a template lock file can be upgraded"
+        ))
+        .with_path(project_path)
+    })?;
+
+    let mut changes = vec![];
+    for entry in &lock.templates {
+        let example = entry.find_example()?;
+        let parameters = entry.example_parameters(project_path)?;
+        let new_contents = generated_contents(&example, &parameters)?;
+        let mut files = BTreeMap::new();
+        let mut kept_base = BTreeMap::new();
+
+        let paths = new_contents
+            .keys()
+            .chain(entry.files.keys())
+            .collect::<BTreeSet<_>>();
+        for path in paths {
+            let target = project_path.join(path);
+            let current = if target.is_file() {
+                Some(fs::read(&target).with_path(&target)?)
+            } else {
+                None
+            };
+            let original_hash = entry.files.get(path);
+            let new = new_contents.get(path);
+            let (status, action) = upgrade_file(
+                original_hash.map(String::as_str),
+                entry.base.get(path).map(String::as_str),
+                current.as_deref(),
+                new.map(Vec::as_slice),
+            );
+            // The lock records the hash of the template output stored as the base, even if it was
+            // merged, files keep their recorded hash and base if the new output was not written
+            let hash = match &action {
+                UpgradeAction::Remove => None,
+                _ if new.is_none() => None,
+                UpgradeAction::Write(_) => new.map(|new| sha256(new)),
+                _ if current.as_ref() == new => new.map(|new| sha256(new)),
+                _ => {
+                    kept_base.extend(entry.base.get_key_value(path));
+                    original_hash.cloned()
+                }
+            };
+            if let Some(hash) = hash {
+                files.insert(path.clone(), hash);
+            }
+            let Some(status) = status else {
+                continue;
+            };
+            if !dry_run {
+                match action {
+                    UpgradeAction::Write(contents) => {
+                        if let Some(parent) = target.parent() {
+                            fs::create_dir_all(parent).with_path(parent)?;
+                        }
+                        fs::write(&target, contents).with_path(&target)?;
+                    }
+                    UpgradeAction::WriteRejected(contents) => {
+                        let rejected = rejected_path(&target);
+                        fs::write(&rejected, contents).with_path(&rejected)?;
+                    }
+                    UpgradeAction::Remove => fs::remove_file(&target).with_path(&target)?,
+                    UpgradeAction::None => {}
+                }
+            }
+            changes.push(UpgradeChange {
+                path: path.clone(),
+                status,
+            });
+        }
+
+        if !dry_run {
+            let mut new_entry = template_lock_entry(&example, &parameters, files)?;
+            for (path, base) in kept_base {
+                new_entry.base.insert(path.clone(), base.clone());
+            }
+            update_template_lock(project_path, new_entry)?;
+        }
+    }
+    Ok(changes)
+}
+
+/// Decides how to upgrade a file, from the hash and text of the recorded template output, the
+/// current contents and the new template output
+fn upgrade_file(
+    original_hash: Option<&str>,
+    base: Option<&str>,
+    current: Option<&[u8]>,
+    new: Option<&[u8]>,
+) -> (Option<UpgradeStatus>, UpgradeAction) {
+    let unchanged = (None, UpgradeAction::None);
+    match (current, new) {
+        (None, None) => unchanged,
+        (None, Some(new)) => match original_hash {
+            None => (
+                Some(UpgradeStatus::Added),
+                UpgradeAction::Write(new.to_vec()),
+            ),
+            Some(_) => (Some(UpgradeStatus::Kept), UpgradeAction::None),
+        },
+        (Some(current), None) => {
+            if original_hash == Some(sha256(current).as_str()) {
+                (Some(UpgradeStatus::Removed), UpgradeAction::Remove)
+            } else {
+                (Some(UpgradeStatus::Kept), UpgradeAction::None)
+            }
+        }
+        (Some(current), Some(new)) => {
+            if current == new || original_hash == Some(sha256(new).as_str()) {
+                return unchanged;
+            }
+            if original_hash == Some(sha256(current).as_str()) {
+                return (
+                    Some(UpgradeStatus::Updated),
+                    UpgradeAction::Write(new.to_vec()),
+                );
+            }
+            // Files which are new in the template, but already exist, have no base to merge with
+            let base = original_hash.and(base);
+            match (base, std::str::from_utf8(current), std::str::from_utf8(new)) {
+                (Some(base), Ok(current_text), Ok(new_text)) => {
+                    match diffy::merge(base, current_text, new_text) {
+                        Ok(merged) if merged.as_bytes() == current => unchanged,
+                        Ok(merged) => (
+                            Some(UpgradeStatus::Merged),
+                            UpgradeAction::Write(merged.into_bytes()),
+                        ),
+                        Err(conflicts) => (
+                            Some(UpgradeStatus::Conflict),
+                            UpgradeAction::Write(conflicts.into_bytes()),
+                        ),
+                    }
+                }
+                _ => (
+                    Some(UpgradeStatus::Rejected),
+                    UpgradeAction::WriteRejected(new.to_vec()),
+                ),
+            }
+        }
+    }
+}
+
+fn rejected_path(target: &Path) -> PathBuf {
+    let mut file_name = target.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".rej");
+    target.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instantiate_example;
+    use crate::lock::{read_template_lock, update_template_lock};
+    use crate::model::TargetExistsResolveMode;
+    use crate::sha256;
+    use crate::test_fixtures::TestProject;
+    use crate::upgrade::{upgrade_file, upgrade_project, UpgradeChange, UpgradeStatus};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    pub fn upgrade_file_decisions() {
+        let status = |original: &str, base: &str, current: Option<&str>, new: Option<&str>| {
+            upgrade_file(
+                Some(&sha256(original.as_bytes())),
+                Some(base),
+                current.map(str::as_bytes),
+                new.map(str::as_bytes),
+            )
+            .0
+        };
+        let base = "a\nb\nc\n";
+
+        assert_eq!(status(base, base, Some(base), Some(base)), None);
+        assert_eq!(
+            status(base, base, Some(base), Some("a\nb\nc\nd\n")),
+            Some(UpgradeStatus::Updated)
+        );
+        assert_eq!(status(base, base, Some("a\nb\nc\nd\n"), Some(base)), None);
+        assert_eq!(
+            status(base, base, Some("x\nb\nc\n"), Some("a\nb\ny\n")),
+            Some(UpgradeStatus::Merged)
+        );
+        assert_eq!(
+            status(base, base, Some("x\nb\nc\n"), Some("y\nb\nc\n")),
+            Some(UpgradeStatus::Conflict)
+        );
+        assert_eq!(
+            status(base, base, None, Some("a\n")),
+            Some(UpgradeStatus::Kept)
+        );
+        assert_eq!(
+            status(base, base, Some(base), None),
+            Some(UpgradeStatus::Removed)
+        );
+        assert_eq!(
+            status(base, base, Some("x\n"), None),
+            Some(UpgradeStatus::Kept)
+        );
+        assert_eq!(
+            upgrade_file(None, None, None, Some(b"new")).0,
+            Some(UpgradeStatus::Added)
+        );
+        assert_eq!(
+            upgrade_file(None, None, Some(b"user\n"), Some(b"new\n")).0,
+            Some(UpgradeStatus::Rejected)
+        );
+        assert_eq!(
+            upgrade_file(Some(&sha256(b"\xff")), None, Some(b"\xfe"), Some(b"\xfd")).0,
+            Some(UpgradeStatus::Rejected)
+        );
+    }
+
+    #[test]
+    pub fn upgrade_generated_project() {
+        let project = TestProject::generated("rust-default");
+        let target_path = project.path();
+        assert!(upgrade_project(target_path, false).unwrap().is_empty());
+
+        // Simulates an older template, which generated an extra comment in Cargo.toml and a file
+        // since removed, and a user who added a dependency
+        let cargo_toml = target_path.join("Cargo.toml");
+        let generated = std::fs::read_to_string(&cargo_toml).unwrap();
+        let old_output = generated.replacen("edition", "# old template\nedition", 1);
+        let user_version = old_output.replace("[dependencies]", "[dependencies]\nuser = \"1\"");
+        std::fs::write(&cargo_toml, &user_version).unwrap();
+        std::fs::write(target_path.join("old.txt"), "old").unwrap();
+
+        let mut lock = read_template_lock(target_path).unwrap().unwrap();
+        let entry = &mut lock.templates[0];
+        entry
+            .files
+            .insert(PathBuf::from("Cargo.toml"), sha256(old_output.as_bytes()));
+        entry
+            .base
+            .insert(PathBuf::from("Cargo.toml"), old_output.clone());
+        entry.files.insert(PathBuf::from("old.txt"), sha256(b"old"));
+        std::fs::remove_file(target_path.join(".golem-template.json")).unwrap();
+        update_template_lock(target_path, lock.templates[0].clone()).unwrap();
+
+        let changes = upgrade_project(target_path, true).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                UpgradeChange {
+                    path: PathBuf::from("Cargo.toml"),
+                    status: UpgradeStatus::Merged
+                },
+                UpgradeChange {
+                    path: PathBuf::from("old.txt"),
+                    status: UpgradeStatus::Removed
+                },
+            ]
+        );
+        assert!(target_path.join("old.txt").exists());
+
+        upgrade_project(target_path, false).unwrap();
+        let upgraded = std::fs::read_to_string(&cargo_toml).unwrap();
+        assert!(!upgraded.contains("# old template"));
+        assert!(upgraded.contains("user = \"1\""));
+        assert!(!target_path.join("old.txt").exists());
+        assert!(
+            !read_template_lock(target_path).unwrap().unwrap().templates[0]
+                .files
+                .contains_key(Path::new("old.txt"))
+        );
+    }
+
+    #[test]
+    pub fn upgrade_keeps_merged_contents() {
+        let project = TestProject::new("python-default");
+        let target_path = project.path();
+        std::fs::create_dir_all(target_path).unwrap();
+        std::fs::write(target_path.join(".gitignore"), "user-line\n").unwrap();
+        instantiate_example(
+            &project.example,
+            &project.parameters,
+            TargetExistsResolveMode::MergeOrSkip,
+        )
+        .unwrap();
+        let merged = std::fs::read_to_string(target_path.join(".gitignore")).unwrap();
+        assert!(merged.lines().any(|line| line == "user-line"));
+
+        // The merged user line is a change of the generated file, not an outdated template output
+        assert!(upgrade_project(target_path, false).unwrap().is_empty());
+        assert_eq!(
+            std::fs::read_to_string(target_path.join(".gitignore")).unwrap(),
+            merged
+        );
+    }
+}
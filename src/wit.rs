@@ -0,0 +1,397 @@
+use include_dir::Dir;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+static COMMENT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)/\*.*?\*/|//[^\n]*").unwrap());
+
+const SEMVER_PATTERN: &str = r"[0-9]+\.[0-9]+\.[0-9]+(?:-[0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*)?(?:\+[0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*)?";
+
+static PACKAGE_DECLARATION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?m)^\s*package\s+([a-z][a-z0-9-]*:[a-z][a-z0-9-]*(?:@{SEMVER_PATTERN})?)\s*[;{{]"
+    ))
+    .unwrap()
+});
+
+static PACKAGE_REFERENCE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?m)(?:^|[\s{{}};])(?:use|import|export|include)\s+([a-z][a-z0-9-]*):([a-z][a-z0-9-]*)(?:/[a-z][a-z0-9-]*)?(?:@({SEMVER_PATTERN}))?"
+    ))
+    .unwrap()
+});
+
+static INTERFACE_DECLARATION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)(?:^|[\s{};])interface\s+([a-z][a-z0-9-]*)\s*\{").unwrap());
+
+static WORLD_DECLARATION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)(?:^|[\s{};])world\s+([a-z][a-z0-9-]*)\s*\{").unwrap());
+
+static WORLD_ITEM_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)(?:^|[\s{};])(import|export|include)\s+([^;{]+)").unwrap());
+
+/// Golem host interfaces, used for `requiresGolemHostWIT`
+pub const GOLEM_HOST_WIT_PRESET: &[&str] = &["golem:api", "golem:rpc"];
+
+/// WASI interfaces supported by Golem, used for `requiresWASI`
+pub const WASI_PRESET: &[&str] = &[
+    "wasi:blobstore",
+    "wasi:cli",
+    "wasi:clocks",
+    "wasi:filesystem",
+    "wasi:http",
+    "wasi:io",
+    "wasi:keyvalue",
+    "wasi:logging",
+    "wasi:random",
+    "wasi:sockets",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WitPackageName {
+    pub namespace: String,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl WitPackageName {
+    pub fn from_string(s: impl AsRef<str>) -> Option<WitPackageName> {
+        let s = s.as_ref();
+        let (id, version) = match s.split_once('@') {
+            Some((id, version)) => (id, Some(version.to_string())),
+            None => (s, None),
+        };
+        match id.split(':').collect::<Vec<_>>().as_slice() {
+            &[namespace, name] if !namespace.is_empty() && !name.is_empty() => {
+                Some(WitPackageName {
+                    namespace: namespace.to_string(),
+                    name: name.to_string(),
+                    version,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks if the other package name is referenced by this one, unversioned names match every version
+    pub fn matches(&self, other: &WitPackageName) -> bool {
+        self.namespace == other.namespace
+            && self.name == other.name
+            && (self.version.is_none() || self.version == other.version)
+    }
+}
+
+impl fmt::Display for WitPackageName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, "@{}", version)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for WitPackageName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WitPackageName::from_string(s).ok_or(format!(
+            "Unexpected WIT package name {s}. Must be in 'namespace:name' or 'namespace:name@version' format"
+        ))
+    }
+}
+
+/// A WIT package available in a WIT deps bundle
+#[derive(Debug, Clone)]
+pub struct WitPackage {
+    pub name: WitPackageName,
+    pub path: PathBuf,
+    pub dependencies: BTreeSet<WitPackageName>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WitBundle {
+    pub packages: Vec<WitPackage>,
+}
+
+impl WitBundle {
+    /// Collects the packages from a WIT deps directory, where every subdirectory is a package
+    pub fn from_dir(dir: &Dir<'_>) -> WitBundle {
+        WitBundle::from_sources(dir.dirs().map(|package_dir| {
+            (
+                package_dir.path().to_path_buf(),
+                package_dir
+                    .files()
+                    .filter_map(|file| file.contents_utf8())
+                    .collect(),
+            )
+        }))
+    }
+
+    /// Collects the packages from the WIT sources of the package directories, given by their
+    /// paths in the bundle
+    pub fn from_sources<'a>(
+        package_dirs: impl IntoIterator<Item = (PathBuf, Vec<&'a str>)>,
+    ) -> WitBundle {
+        let mut packages = vec![];
+        for (path, sources) in package_dirs {
+            let mut name = None;
+            let mut dependencies = BTreeSet::new();
+            for source in sources {
+                if name.is_none() {
+                    name = parse_package_declaration(source);
+                }
+                dependencies.extend(parse_package_references(source));
+            }
+            if let Some(name) = name {
+                dependencies.retain(|dependency| !dependency.matches(&name));
+                packages.push(WitPackage {
+                    name,
+                    path,
+                    dependencies,
+                });
+            }
+        }
+        WitBundle { packages }
+    }
+
+    /// Packages matching the name, unversioned names match every version
+    pub fn find(&self, name: &WitPackageName) -> Vec<&WitPackage> {
+        self.packages
+            .iter()
+            .filter(|package| name.matches(&package.name))
+            .collect()
+    }
+
+    /// Package stored at the given path of the bundle
+    pub fn find_by_path(&self, path: &Path) -> Option<&WitPackage> {
+        self.packages.iter().find(|package| package.path == path)
+    }
+
+    /// Resolves the packages and all their transitive dependencies, returns their paths in the bundle
+    pub fn resolve_closure<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a WitPackageName>,
+    ) -> Result<BTreeSet<PathBuf>, String> {
+        let mut result = BTreeSet::<PathBuf>::new();
+        let mut queue: Vec<(WitPackageName, Option<&Path>)> =
+            names.into_iter().map(|name| (name.clone(), None)).collect();
+
+        while let Some((name, referenced_from)) = queue.pop() {
+            let packages = self.find(&name);
+            if packages.is_empty() {
+                return Err(match referenced_from {
+                    Some(referenced_from) => format!(
+                        "Could not find WIT package {}, referenced from {}",
+                        name,
+                        referenced_from.display()
+                    ),
+                    None => format!("Could not find WIT package {}", name),
+                });
+            }
+            for package in packages {
+                if result.insert(package.path.clone()) {
+                    queue.extend(
+                        package
+                            .dependencies
+                            .iter()
+                            .map(|dependency| (dependency.clone(), Some(package.path.as_path()))),
+                    );
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// A world declared in a WIT file, with its imports, exports and includes as written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WitWorld {
+    pub name: String,
+    pub imports: Vec<String>,
+    pub exports: Vec<String>,
+    pub includes: Vec<String>,
+}
+
+/// Collects the worlds declared in the source, nested inline interfaces are not expanded
+pub fn parse_worlds(source: &str) -> Vec<WitWorld> {
+    let source = strip_comments(source);
+    WORLD_DECLARATION_REGEX
+        .captures_iter(&source)
+        .map(|captures| {
+            let body_start = captures.get(0).unwrap().end();
+            let mut depth = 1;
+            let body_end = source[body_start..]
+                .char_indices()
+                .find(|(_, char)| {
+                    match char {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    depth == 0
+                })
+                .map(|(index, _)| body_start + index)
+                .unwrap_or(source.len());
+
+            let mut world = WitWorld {
+                name: captures[1].to_string(),
+                imports: vec![],
+                exports: vec![],
+                includes: vec![],
+            };
+            for item in WORLD_ITEM_REGEX.captures_iter(&source[body_start..body_end]) {
+                let name = item[2].trim().to_string();
+                match &item[1] {
+                    "import" => world.imports.push(name),
+                    "export" => world.exports.push(name),
+                    _ => world.includes.push(name),
+                }
+            }
+            world
+        })
+        .collect()
+}
+
+/// Collects the names of the interfaces declared in the source
+pub fn parse_interfaces(source: &str) -> Vec<String> {
+    INTERFACE_DECLARATION_REGEX
+        .captures_iter(&strip_comments(source))
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+fn strip_comments(source: &str) -> String {
+    COMMENT_REGEX.replace_all(source, "").to_string()
+}
+
+pub fn parse_package_declaration(source: &str) -> Option<WitPackageName> {
+    PACKAGE_DECLARATION_REGEX
+        .captures(&strip_comments(source))
+        .and_then(|captures| WitPackageName::from_string(&captures[1]))
+}
+
+/// Collects the foreign packages referenced by `use`, `import`, `export` and `include` statements
+pub fn parse_package_references(source: &str) -> BTreeSet<WitPackageName> {
+    PACKAGE_REFERENCE_REGEX
+        .captures_iter(&strip_comments(source))
+        .map(|captures| WitPackageName {
+            namespace: captures[1].to_string(),
+            name: captures[2].to_string(),
+            version: captures.get(3).map(|version| version.as_str().to_string()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wit::{
+        parse_interfaces, parse_package_declaration, parse_package_references, parse_worlds,
+        WitPackageName, WitWorld,
+    };
+
+    #[test]
+    pub fn package_declaration() {
+        assert_eq!(
+            parse_package_declaration("// package wasi:http;\npackage wasi:io@0.2.0;\n"),
+            WitPackageName::from_string("wasi:io@0.2.0")
+        );
+        assert_eq!(parse_package_declaration("interface run {}"), None);
+    }
+
+    #[test]
+    pub fn package_references() {
+        let source = r#"
+            package pack:name;
+
+            /// Uses wasi:keyvalue/types, but only in a comment
+            interface api {
+              use wasi:clocks/wall-clock@0.2.0.{datetime};
+              use wasi:io/poll@0.2.0-rc.1.{pollable};
+              use types.{key};
+            }
+
+            world component-name {
+              import golem:api/host@1.1.0;
+              import wasi:blobstore/blobstore;
+              import get-key: func() -> string;
+              include wasi:cli/imports@0.2.0;
+              export wasi:http/incoming-handler@0.2.0;
+              export api;
+            }
+
+            world inline { import wasi:logging/logging; export wasi:random/random@0.2.0; }
+        "#;
+
+        assert_eq!(
+            parse_package_references(source)
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "golem:api@1.1.0",
+                "wasi:blobstore",
+                "wasi:cli@0.2.0",
+                "wasi:clocks@0.2.0",
+                "wasi:http@0.2.0",
+                "wasi:io@0.2.0-rc.1",
+                "wasi:logging",
+                "wasi:random@0.2.0",
+            ]
+        );
+    }
+
+    #[test]
+    pub fn worlds_and_interfaces() {
+        let source = r#"
+            package pack:name;
+
+            // interface commented {}
+            interface api {
+              add: func(value: u64);
+            }
+
+            world component-name {
+              import golem:api/host@1.1.0;
+              // import wasi:logging/logging;
+              import get-key: func() -> string;
+              include wasi:cli/imports@0.2.0;
+              export inline: interface {
+                run: func();
+              }
+              export api;
+            }
+
+            world single-line { import wasi:io/poll; export api; }
+        "#;
+
+        assert_eq!(parse_interfaces(source), vec!["api"]);
+        assert_eq!(
+            parse_worlds(source),
+            vec![
+                WitWorld {
+                    name: "component-name".to_string(),
+                    imports: vec![
+                        "golem:api/host@1.1.0".to_string(),
+                        "get-key: func() -> string".to_string()
+                    ],
+                    exports: vec!["inline: interface".to_string(), "api".to_string()],
+                    includes: vec!["wasi:cli/imports@0.2.0".to_string()],
+                },
+                WitWorld {
+                    name: "single-line".to_string(),
+                    imports: vec!["wasi:io/poll".to_string()],
+                    exports: vec!["api".to_string()],
+                    includes: vec![],
+                }
+            ]
+        );
+    }
+}
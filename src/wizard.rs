@@ -0,0 +1,479 @@
+//! Interactive mode of the `new` command
+//!
+//! The questions are asked through the [`Prompt`] trait, so the flow can be driven by scripted
+//! answers in tests and by [`TerminalPrompt`] in the CLI.
+
+use crate::model::{ComponentName, Example, ExampleParameters, GuestLanguage, PackageName};
+use crate::planned_files;
+use itertools::Itertools;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Label of the option disabling a filter
+const ANY_OPTION: &str = "any";
+
+const DEFAULT_PACKAGE_NAME: &str = "golem:component";
+
+pub trait Prompt {
+    /// Asks to choose one of the options, returns the index of the chosen option
+    fn select(&mut self, message: &str, options: &[String], default: usize) -> io::Result<usize>;
+
+    /// Asks for a line of text, repeating the question until `validate` accepts the answer
+    fn input(
+        &mut self,
+        message: &str,
+        default: Option<&str>,
+        validate: &dyn Fn(&str) -> Result<(), String>,
+    ) -> io::Result<String>;
+
+    /// Asks a yes or no question
+    fn confirm(&mut self, message: &str, default: bool) -> io::Result<bool>;
+
+    /// Shows information without expecting an answer
+    fn show(&mut self, text: &str) -> io::Result<()>;
+}
+
+/// Line based prompt, reading the answers from the input and writing the questions to the output
+pub struct TerminalPrompt<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+}
+
+impl TerminalPrompt<io::StdinLock<'static>, io::Stderr> {
+    /// Prompt on stdin, asking the questions on stderr, so stdout only contains the results
+    pub fn stdio() -> Self {
+        TerminalPrompt::new(io::stdin().lock(), io::stderr())
+    }
+}
+
+impl<R: BufRead, W: Write> TerminalPrompt<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        TerminalPrompt { input, output }
+    }
+
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Input ended before all questions were answered",
+            ));
+        }
+        Ok(line.trim().to_string())
+    }
+}
+
+impl<R: BufRead, W: Write> Prompt for TerminalPrompt<R, W> {
+    fn select(&mut self, message: &str, options: &[String], default: usize) -> io::Result<usize> {
+        writeln!(self.output, "{message}")?;
+        for (index, option) in options.iter().enumerate() {
+            writeln!(self.output, "  {:>2}) {}", index + 1, option)?;
+        }
+        loop {
+            write!(self.output, "Choose [{}]: ", default + 1)?;
+            self.output.flush()?;
+            let answer = self.read_line()?;
+            if answer.is_empty() {
+                return Ok(default);
+            }
+            let chosen = answer
+                .parse::<usize>()
+                .ok()
+                .filter(|index| (1..=options.len()).contains(index))
+                .map(|index| index - 1)
+                .or_else(|| options.iter().position(|option| option == &answer));
+            match chosen {
+                Some(index) => return Ok(index),
+                None => writeln!(
+                    self.output,
+                    "Expected a number between 1 and {}",
+                    options.len()
+                )?,
+            }
+        }
+    }
+
+    fn input(
+        &mut self,
+        message: &str,
+        default: Option<&str>,
+        validate: &dyn Fn(&str) -> Result<(), String>,
+    ) -> io::Result<String> {
+        loop {
+            match default {
+                Some(default) => write!(self.output, "{message} [{default}]: ")?,
+                None => write!(self.output, "{message}: ")?,
+            }
+            self.output.flush()?;
+            let answer = match (self.read_line()?, default) {
+                (answer, Some(default)) if answer.is_empty() => default.to_string(),
+                (answer, _) => answer,
+            };
+            match validate(&answer) {
+                Ok(()) => return Ok(answer),
+                Err(err) => writeln!(self.output, "{err}")?,
+            }
+        }
+    }
+
+    fn confirm(&mut self, message: &str, default: bool) -> io::Result<bool> {
+        loop {
+            write!(
+                self.output,
+                "{message} [{}]: ",
+                if default { "Y/n" } else { "y/N" }
+            )?;
+            self.output.flush()?;
+            match self.read_line()?.to_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => writeln!(self.output, "Expected yes or no")?,
+            }
+        }
+    }
+
+    fn show(&mut self, text: &str) -> io::Result<()> {
+        writeln!(self.output, "{text}")
+    }
+}
+
+/// Asks for the example and its parameters, returns `None` if the summary was not confirmed
+///
+/// The target path of the component is selected by `target_path` based on the component name.
+pub fn run_wizard(
+    prompt: &mut dyn Prompt,
+    examples: &[Example],
+    target_path: &dyn Fn(&ComponentName) -> PathBuf,
+) -> io::Result<Option<(Example, ExampleParameters)>> {
+    if examples.is_empty() {
+        return Err(io::Error::other("No examples are available"));
+    }
+
+    let tiers = examples
+        .iter()
+        .map(|example| example.language.tier())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let examples = if tiers.len() > 1 {
+        let options = with_any_option(tiers.iter().map(|tier| tier.name().to_string()));
+        let min_tier = match prompt.select("Minimum language tier", &options, 0)? {
+            0 => None,
+            chosen => Some(&tiers[chosen - 1]),
+        };
+        examples
+            .iter()
+            .filter(|example| match min_tier {
+                Some(min_tier) => example.language.tier() <= *min_tier,
+                None => true,
+            })
+            .collect::<Vec<_>>()
+    } else {
+        examples.iter().collect()
+    };
+
+    let languages = examples
+        .iter()
+        .map(|example| example.language)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let language = if languages.len() > 1 {
+        let options = languages
+            .iter()
+            .map(|language| language.name().to_string())
+            .collect::<Vec<_>>();
+        let default = languages
+            .iter()
+            .position(|language| *language == GuestLanguage::Rust)
+            .unwrap_or_default();
+        languages[prompt.select("Language", &options, default)?]
+    } else {
+        languages[0]
+    };
+    let examples = examples
+        .into_iter()
+        .filter(|example| example.language == language)
+        .collect::<Vec<_>>();
+
+    let tags = examples
+        .iter()
+        .flat_map(|example| example.tags.iter().cloned())
+        .collect::<BTreeSet<_>>();
+    let examples = if tags.is_empty() {
+        examples
+    } else {
+        let options = with_any_option(tags.iter().cloned());
+        let chosen = prompt.select("Tag", &options, 0)?;
+        examples
+            .into_iter()
+            .filter(|example| chosen == 0 || example.tags.contains(&options[chosen]))
+            .collect()
+    };
+
+    let example = if examples.len() > 1 {
+        let options = examples
+            .iter()
+            .map(|example| format!("{} - {}", example.name, example.description))
+            .collect::<Vec<_>>();
+        let default = examples
+            .iter()
+            .position(|example| example.name.as_string().ends_with("-default"))
+            .unwrap_or_default();
+        examples[prompt.select("Example", &options, default)?].clone()
+    } else {
+        examples[0].clone()
+    };
+
+    let component_name = prompt.input("Component name", None, &|name| {
+        ComponentName::from_string(name).map(|_| ())
+    })?;
+    let package_name = prompt.input(
+        "Package name (namespace:name)",
+        Some(DEFAULT_PACKAGE_NAME),
+        &|name| {
+            PackageName::from_string(name)
+                .map(|_| ())
+                .ok_or_else(|| format!("Invalid package name {name}, expected namespace:name"))
+        },
+    )?;
+
+    let mut template_parameters = BTreeMap::new();
+    for parameter in &example.parameters {
+        let value = prompt.input(
+            &parameter.description,
+            parameter.default.as_deref(),
+            &|value| {
+                if value.is_empty() {
+                    Err(format!("A value is required for {}", parameter.name))
+                } else {
+                    Ok(())
+                }
+            },
+        )?;
+        template_parameters.insert(parameter.name.clone(), value);
+    }
+
+    let parameters = ExampleParameters {
+        component_name: ComponentName::new(&component_name),
+        package_name: PackageName::from_string(&package_name).unwrap(),
+        target_path: target_path(&ComponentName::new(&component_name)),
+        template_parameters,
+    };
+
+    prompt.show(&render_file_tree(
+        &component_name,
+        &planned_files(&example, &parameters).map_err(io::Error::other)?,
+    ))?;
+    if prompt.confirm(
+        &format!("Create {} from {}?", component_name, example.name),
+        true,
+    )? {
+        Ok(Some((example, parameters)))
+    } else {
+        Ok(None)
+    }
+}
+
+fn with_any_option(options: impl Iterator<Item = String>) -> Vec<String> {
+    std::iter::once(ANY_OPTION.to_string())
+        .chain(options)
+        .collect()
+}
+
+/// Renders the paths as a tree under `root`, in the style of `list-app-examples`
+pub fn render_file_tree(root: &str, files: &[PathBuf]) -> String {
+    #[derive(Default)]
+    struct Node(BTreeMap<String, Node>);
+
+    fn render(node: &Node, indent: &str, lines: &mut Vec<String>) {
+        for (index, (name, child)) in node.0.iter().enumerate() {
+            let last = index == node.0.len() - 1;
+            lines.push(format!("{indent}{} {name}", if last { "└─" } else { "├─" }));
+            render(
+                child,
+                &format!("{indent}{}", if last { "   " } else { "│  " }),
+                lines,
+            );
+        }
+    }
+
+    let mut tree = Node::default();
+    for file in files {
+        file.components().fold(&mut tree, |node, component| {
+            node.0
+                .entry(component.as_os_str().to_string_lossy().to_string())
+                .or_default()
+        });
+    }
+
+    let mut lines = vec![root.to_string()];
+    render(&tree, "", &mut lines);
+    lines.into_iter().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::all_standalone_examples;
+    use crate::model::ExampleName;
+    use crate::wizard::{render_file_tree, run_wizard, Prompt, TerminalPrompt};
+    use std::collections::VecDeque;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    /// Answers the questions from a script, failing on unexpected questions
+    struct ScriptedPrompt {
+        answers: VecDeque<(&'static str, &'static str)>,
+        shown: Vec<String>,
+    }
+
+    impl ScriptedPrompt {
+        fn new(answers: &[(&'static str, &'static str)]) -> Self {
+            ScriptedPrompt {
+                answers: answers.iter().cloned().collect(),
+                shown: vec![],
+            }
+        }
+
+        fn answer(&mut self, message: &str) -> &'static str {
+            let (expected, answer) = self
+                .answers
+                .pop_front()
+                .unwrap_or_else(|| panic!("Unexpected question: {message}"));
+            assert!(
+                message.starts_with(expected),
+                "Expected question {expected}, got {message}"
+            );
+            answer
+        }
+    }
+
+    impl Prompt for ScriptedPrompt {
+        fn select(&mut self, message: &str, options: &[String], _: usize) -> io::Result<usize> {
+            let answer = self.answer(message);
+            Ok(options
+                .iter()
+                .position(|option| option == answer || option.starts_with(&format!("{answer} - ")))
+                .unwrap_or_else(|| panic!("Unknown option {answer} in {options:?}")))
+        }
+
+        fn input(
+            &mut self,
+            message: &str,
+            default: Option<&str>,
+            validate: &dyn Fn(&str) -> Result<(), String>,
+        ) -> io::Result<String> {
+            let answer = match (self.answer(message), default) {
+                ("", Some(default)) => default,
+                (answer, _) => answer,
+            };
+            validate(answer).map_err(io::Error::other)?;
+            Ok(answer.to_string())
+        }
+
+        fn confirm(&mut self, message: &str, _: bool) -> io::Result<bool> {
+            Ok(self.answer(message) == "yes")
+        }
+
+        fn show(&mut self, text: &str) -> io::Result<()> {
+            self.shown.push(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    pub fn wizard_creates_parameters_from_answers() {
+        let mut prompt = ScriptedPrompt::new(&[
+            ("Language", "Rust"),
+            ("Tag", "any"),
+            ("Example", "rust-default"),
+            ("Component name", "my-component"),
+            ("Package name", "my:pack"),
+            ("Create", "yes"),
+        ]);
+        let (example, parameters) = run_wizard(&mut prompt, &all_standalone_examples(), &|name| {
+            Path::new("/non-existing-target").join(name.as_str())
+        })
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(example.name, ExampleName::from_string("rust-default"));
+        assert_eq!(parameters.component_name.as_str(), "my-component");
+        assert_eq!(parameters.package_name.to_string_with_colon(), "my:pack");
+        assert_eq!(
+            parameters.target_path,
+            PathBuf::from("/non-existing-target/my-component")
+        );
+        assert!(prompt.answers.is_empty());
+        assert!(prompt.shown[0].starts_with("my-component\n"));
+        assert!(prompt.shown[0].contains("Cargo.toml"));
+    }
+
+    #[test]
+    pub fn wizard_filters_by_tag_and_uses_defaults() {
+        let mut prompt = ScriptedPrompt::new(&[
+            ("Language", "Go"),
+            ("Tag", "minimal"),
+            ("Component name", "minimal"),
+            ("Package name", ""),
+            ("Create", "no"),
+        ]);
+        let result = run_wizard(&mut prompt, &all_standalone_examples(), &|name| {
+            Path::new("/non-existing-target").join(name.as_str())
+        })
+        .unwrap();
+
+        assert!(result.is_none());
+        assert!(prompt.answers.is_empty());
+        assert!(prompt.shown[0].contains("main.go"));
+    }
+
+    #[test]
+    pub fn terminal_prompt_repeats_invalid_answers() {
+        let mut output = vec![];
+        let mut prompt =
+            TerminalPrompt::new(&b"0\n2\n1nvalid\nvalid\n\nmaybe\nn\n"[..], &mut output);
+
+        let options = vec!["first".to_string(), "second".to_string()];
+        assert_eq!(prompt.select("Select", &options, 0).unwrap(), 1);
+        let validate = |value: &str| {
+            if value.starts_with(char::is_alphabetic) {
+                Ok(())
+            } else {
+                Err("Must start with a letter".to_string())
+            }
+        };
+        assert_eq!(prompt.input("Name", None, &validate).unwrap(), "valid");
+        assert_eq!(
+            prompt.input("Package", Some("default"), &validate).unwrap(),
+            "default"
+        );
+        assert!(!prompt.confirm("Continue?", true).unwrap());
+        assert!(prompt.confirm("Continue?", true).is_err());
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Expected a number between 1 and 2"));
+        assert!(output.contains("Must start with a letter"));
+        assert!(output.contains("Expected yes or no"));
+    }
+
+    #[test]
+    pub fn file_tree() {
+        let tree = render_file_tree(
+            "component",
+            &[
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("wit/component.wit"),
+                PathBuf::from("wit/deps/io/streams.wit"),
+            ],
+        );
+        assert_eq!(
+            tree,
+            "component\n├─ Cargo.toml\n├─ src\n│  └─ lib.rs\n└─ wit\n   ├─ component.wit\n   └─ deps\n      └─ io\n         └─ streams.wit"
+        );
+    }
+}